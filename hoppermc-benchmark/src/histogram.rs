@@ -0,0 +1,128 @@
+//! Lock-free log-linear latency histogram, for percentile reporting without
+//! a lock or a per-sample allocation on the hot path.
+//!
+//! Buckets are laid out as a handful of linear sub-buckets per power-of-two
+//! octave: a value's octave is its highest set bit (`63 -
+//! v.leading_zeros()`), and [`SUBBUCKETS_PER_OCTAVE`] further splits that
+//! octave linearly for better resolution near common values than a pure
+//! power-of-two bucketing would give.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Linear sub-buckets within each power-of-two octave.
+const SUBBUCKETS_PER_OCTAVE: u64 = 4;
+
+/// Octaves tracked individually before values are clamped into the top
+/// bucket — `2^40` microseconds is well over a day, far past anything a
+/// single chunk generation or FUSE read should ever take.
+const MAX_EXPONENT: u32 = 40;
+
+const BUCKET_COUNT: usize = (MAX_EXPONENT as usize + 1) * SUBBUCKETS_PER_OCTAVE as usize;
+
+#[derive(Debug)]
+pub struct Histogram {
+    buckets: Vec<AtomicU64>,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            buckets: (0..BUCKET_COUNT).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    /// Records one sample, in microseconds.
+    pub fn record(&self, value_us: u64) {
+        self.buckets[Self::bucket_index(value_us)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the value at percentile `p` (e.g. `0.5` for p50), as the
+    /// representative (lower-bound) value of the bucket whose cumulative
+    /// count first reaches `ceil(p * total)`. `0` if no samples were
+    /// recorded yet.
+    pub fn percentile(&self, p: f64) -> u64 {
+        let total: u64 = self.buckets.iter().map(|b| b.load(Ordering::Relaxed)).sum();
+        if total == 0 {
+            return 0;
+        }
+
+        let rank = ((p * total as f64).ceil() as u64).max(1);
+        let mut cumulative = 0u64;
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            cumulative += bucket.load(Ordering::Relaxed);
+            if cumulative >= rank {
+                return Self::bucket_value(index);
+            }
+        }
+
+        Self::bucket_value(BUCKET_COUNT - 1)
+    }
+
+    fn bucket_index(value_us: u64) -> usize {
+        let exponent = Self::exponent_of(value_us).min(MAX_EXPONENT);
+        let octave_base = 1u64 << exponent;
+        let octave_width = (octave_base / SUBBUCKETS_PER_OCTAVE).max(1);
+        let sub = ((value_us.saturating_sub(octave_base)) / octave_width).min(SUBBUCKETS_PER_OCTAVE - 1);
+
+        let index = exponent as usize * SUBBUCKETS_PER_OCTAVE as usize + sub as usize;
+        index.min(BUCKET_COUNT - 1)
+    }
+
+    fn bucket_value(index: usize) -> u64 {
+        let exponent = (index / SUBBUCKETS_PER_OCTAVE as usize) as u32;
+        let sub = (index % SUBBUCKETS_PER_OCTAVE as usize) as u64;
+
+        let octave_base = 1u64 << exponent;
+        let octave_width = (octave_base / SUBBUCKETS_PER_OCTAVE).max(1);
+        octave_base + sub * octave_width
+    }
+
+    fn exponent_of(value_us: u64) -> u32 {
+        if value_us == 0 {
+            0
+        } else {
+            63 - value_us.leading_zeros()
+        }
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_histogram_reports_zero() {
+        let hist = Histogram::new();
+        assert_eq!(hist.percentile(0.5), 0);
+        assert_eq!(hist.percentile(0.99), 0);
+    }
+
+    #[test]
+    fn percentiles_track_uniform_samples() {
+        let hist = Histogram::new();
+        for v in 1..=1000u64 {
+            hist.record(v);
+        }
+
+        // Percentiles report the bucket's lower bound: rank 500 falls in the
+        // 448..=511 bucket and rank 990 in the 896..=1023 bucket.
+        let p50 = hist.percentile(0.5);
+        let p99 = hist.percentile(0.99);
+        assert_eq!(p50, 448, "p50 = {p50}");
+        assert_eq!(p99, 896, "p99 = {p99}");
+        assert!(p50 < p99);
+    }
+
+    #[test]
+    fn oversized_values_clamp_into_top_bucket() {
+        let hist = Histogram::new();
+        hist.record(u64::MAX);
+        assert!(hist.percentile(1.0) > 0);
+    }
+}
@@ -1,6 +1,18 @@
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
+mod histogram;
+pub use histogram::Histogram;
+
+mod snapshot;
+pub use snapshot::MetricsSnapshot;
+
+mod system_sampler;
+pub use system_sampler::{SystemSampler, DEFAULT_SAMPLE_INTERVAL};
+
+mod rate_window;
+pub use rate_window::RateWindow;
+
 #[derive(Debug, Default)]
 pub struct BenchmarkMetrics {
     // Generation Stats
@@ -14,6 +26,10 @@ pub struct BenchmarkMetrics {
     pub total_chunks_saved: AtomicUsize,
     pub total_save_time_us: AtomicU64,
 
+    // Tail latency, alongside the averages above.
+    pub generation_latency_us: Histogram,
+    pub fuse_latency_us: Histogram,
+
     // Detailed Breakdown
     pub total_generation_biomes_us: AtomicU64,
     pub total_generation_noise_us: AtomicU64, // Terrain noise
@@ -36,35 +52,118 @@ pub struct BenchmarkMetrics {
     pub total_cache_hits: AtomicUsize,
     pub total_cache_misses: AtomicUsize,
 
+    // Instantaneous (trailing-window) rates, alongside the lifetime
+    // averages computed in `generate_report`.
+    generation_rate: RateWindow,
+    fuse_bytes_rate: RateWindow,
+
+    // Host resource samples (see `SystemSampler`). Disk counters are the
+    // most recent cumulative reading rather than a sum, since `/proc/self/io`
+    // is already cumulative.
+    pub cpu_percent_sum_milli: AtomicU64,
+    pub cpu_percent_peak_milli: AtomicU64,
+    pub system_sample_count: AtomicUsize,
+    pub rss_bytes_sum: AtomicU64,
+    pub rss_bytes_peak: AtomicU64,
+    pub disk_read_bytes: AtomicU64,
+    pub disk_write_bytes: AtomicU64,
+
+    // Per-bucket breakdown (one bucket per dimension, worker thread, or
+    // whatever else the caller wants hotspots broken out by). Aggregate
+    // totals above are still updated in parallel, so existing reporting
+    // and `MetricsSnapshot` stay lifetime-accurate without reading these.
+    bucket_labels: Vec<String>,
+    bucket_chunks_generated: Vec<AtomicUsize>,
+    bucket_generation_time_us: Vec<AtomicU64>,
+    bucket_chunks_loaded: Vec<AtomicUsize>,
+    bucket_load_time_us: Vec<AtomicU64>,
+    bucket_chunks_saved: Vec<AtomicUsize>,
+    bucket_save_time_us: Vec<AtomicU64>,
+    bucket_cache_hits: Vec<AtomicUsize>,
+    bucket_cache_misses: Vec<AtomicUsize>,
+
     // Session
     pub start_time: Option<Instant>,
     pub config_summary: String,
+
+    // Interval reporting: lifetime value of each accumulator the last time
+    // `maybe_report` actually emitted a line, so the next call can log the
+    // delta (this window's rate) instead of a blended lifetime average.
+    last_report_us: AtomicU64,
+    prev_chunks_generated: AtomicUsize,
+    prev_fuse_read_count: AtomicUsize,
+    prev_fuse_bytes_sent: AtomicUsize,
+    prev_cache_hits: AtomicUsize,
+    prev_cache_misses: AtomicUsize,
 }
 
+/// How often [`BenchmarkMetrics::maybe_report`] logs an interval summary.
+pub const STATS_INTERVAL: Duration = Duration::from_secs(10);
+
 impl BenchmarkMetrics {
-    pub fn new(config_summary: String) -> Self {
+    /// `bucket_labels` names the per-bucket breakdown rows (e.g. dimension
+    /// names, or `"worker-0"`/`"worker-1"`) that `record_generation` and
+    /// friends index into; an empty `Vec` falls back to a single `"default"`
+    /// bucket so single-bucket callers don't need to special-case it.
+    pub fn new(config_summary: String, bucket_labels: Vec<String>) -> Self {
+        let bucket_labels = if bucket_labels.is_empty() { vec!["default".to_string()] } else { bucket_labels };
+        let bucket_count = bucket_labels.len();
+
         Self {
             start_time: Some(Instant::now()),
             config_summary,
+            bucket_labels,
+            bucket_chunks_generated: (0..bucket_count).map(|_| AtomicUsize::new(0)).collect(),
+            bucket_generation_time_us: (0..bucket_count).map(|_| AtomicU64::new(0)).collect(),
+            bucket_chunks_loaded: (0..bucket_count).map(|_| AtomicUsize::new(0)).collect(),
+            bucket_load_time_us: (0..bucket_count).map(|_| AtomicU64::new(0)).collect(),
+            bucket_chunks_saved: (0..bucket_count).map(|_| AtomicUsize::new(0)).collect(),
+            bucket_save_time_us: (0..bucket_count).map(|_| AtomicU64::new(0)).collect(),
+            bucket_cache_hits: (0..bucket_count).map(|_| AtomicUsize::new(0)).collect(),
+            bucket_cache_misses: (0..bucket_count).map(|_| AtomicUsize::new(0)).collect(),
             ..Default::default()
         }
     }
 
-    pub fn record_generation(&self, duration: Duration) {
+    /// Clamps an out-of-range bucket index down to the last real bucket
+    /// instead of panicking, so a caller with a stale bucket count (e.g.
+    /// after `--dimensions` shrinks) degrades to miscategorized stats
+    /// rather than crashing a live mount.
+    fn clamp_bucket(&self, bucket: usize) -> usize {
+        bucket.min(self.bucket_labels.len() - 1)
+    }
+
+    pub fn record_generation(&self, bucket: usize, duration: Duration) {
         self.total_chunks_generated.fetch_add(1, Ordering::Relaxed);
         let us = duration.as_micros() as u64;
         self.total_generation_time_us.fetch_add(us, Ordering::Relaxed);
         self.max_generation_time_us.fetch_max(us, Ordering::Relaxed);
+        self.generation_latency_us.record(us);
+        self.generation_rate.record(1);
+
+        let bucket = self.clamp_bucket(bucket);
+        self.bucket_chunks_generated[bucket].fetch_add(1, Ordering::Relaxed);
+        self.bucket_generation_time_us[bucket].fetch_add(us, Ordering::Relaxed);
     }
 
-    pub fn record_load(&self, duration: Duration) {
+    pub fn record_load(&self, bucket: usize, duration: Duration) {
         self.total_chunks_loaded.fetch_add(1, Ordering::Relaxed);
-        self.total_load_time_us.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        let us = duration.as_micros() as u64;
+        self.total_load_time_us.fetch_add(us, Ordering::Relaxed);
+
+        let bucket = self.clamp_bucket(bucket);
+        self.bucket_chunks_loaded[bucket].fetch_add(1, Ordering::Relaxed);
+        self.bucket_load_time_us[bucket].fetch_add(us, Ordering::Relaxed);
     }
 
-    pub fn record_save(&self, duration: Duration) {
+    pub fn record_save(&self, bucket: usize, duration: Duration) {
         self.total_chunks_saved.fetch_add(1, Ordering::Relaxed);
-        self.total_save_time_us.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        let us = duration.as_micros() as u64;
+        self.total_save_time_us.fetch_add(us, Ordering::Relaxed);
+
+        let bucket = self.clamp_bucket(bucket);
+        self.bucket_chunks_saved[bucket].fetch_add(1, Ordering::Relaxed);
+        self.bucket_save_time_us[bucket].fetch_add(us, Ordering::Relaxed);
     }
 
     pub fn record_generation_biomes(&self, duration: Duration) {
@@ -92,9 +191,25 @@ impl BenchmarkMetrics {
     }
 
     pub fn record_fuse_request(&self, duration: Duration, bytes_sent: usize) {
+        let us = duration.as_micros() as u64;
         self.total_fuse_read_count.fetch_add(1, Ordering::Relaxed);
-        self.total_fuse_read_time_us.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        self.total_fuse_read_time_us.fetch_add(us, Ordering::Relaxed);
         self.total_fuse_bytes_sent.fetch_add(bytes_sent, Ordering::Relaxed);
+        self.fuse_latency_us.record(us);
+        self.fuse_bytes_rate.record(bytes_sent as u64);
+    }
+
+    /// Chunks generated per second, averaged over the trailing
+    /// `window_secs` seconds (e.g. `5` or `60`) rather than the whole
+    /// session — surfaces a burst or a stall the lifetime average hides.
+    pub fn current_generation_rate(&self, window_secs: u64) -> f64 {
+        self.generation_rate.rate_per_sec(window_secs)
+    }
+
+    /// FUSE read throughput in MB/s, averaged over the trailing
+    /// `window_secs` seconds.
+    pub fn current_fuse_throughput(&self, window_secs: u64) -> f64 {
+        self.fuse_bytes_rate.rate_per_sec(window_secs) / 1024.0 / 1024.0
     }
 
     pub fn record_chunk_sizes(&self, raw: usize, compressed: usize) {
@@ -102,12 +217,87 @@ impl BenchmarkMetrics {
         self.total_gen_bytes_compressed.fetch_add(compressed, Ordering::Relaxed);
     }
 
-    pub fn record_cache_hit(&self) {
+    pub fn record_cache_hit(&self, bucket: usize) {
         self.total_cache_hits.fetch_add(1, Ordering::Relaxed);
+        self.bucket_cache_hits[self.clamp_bucket(bucket)].fetch_add(1, Ordering::Relaxed);
     }
 
-    pub fn record_cache_miss(&self) {
+    pub fn record_cache_miss(&self, bucket: usize) {
         self.total_cache_misses.fetch_add(1, Ordering::Relaxed);
+        self.bucket_cache_misses[self.clamp_bucket(bucket)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records one [`SystemSampler`] poll's host CPU utilization (as a
+    /// percentage), this process's RSS, and its cumulative disk I/O.
+    pub fn record_system_sample(&self, cpu_percent: f64, rss_bytes: u64, disk_read_bytes: u64, disk_write_bytes: u64) {
+        let cpu_percent_milli = (cpu_percent * 1000.0) as u64;
+        self.cpu_percent_sum_milli.fetch_add(cpu_percent_milli, Ordering::Relaxed);
+        self.cpu_percent_peak_milli.fetch_max(cpu_percent_milli, Ordering::Relaxed);
+        self.system_sample_count.fetch_add(1, Ordering::Relaxed);
+
+        self.rss_bytes_sum.fetch_add(rss_bytes, Ordering::Relaxed);
+        self.rss_bytes_peak.fetch_max(rss_bytes, Ordering::Relaxed);
+
+        self.disk_read_bytes.store(disk_read_bytes, Ordering::Relaxed);
+        self.disk_write_bytes.store(disk_write_bytes, Ordering::Relaxed);
+    }
+
+    /// Logs a one-line summary of *this window's* activity (since the last
+    /// emitted summary) roughly every [`STATS_INTERVAL`], modeled on
+    /// Solana's `BucketMapHolderStats` interval logging. Cheap to call on
+    /// every request: it's a no-op until the interval has elapsed, and a
+    /// `compare_exchange` on `last_report_us` makes sure only one caller
+    /// out of many concurrent ones actually emits for a given window.
+    pub fn maybe_report(&self) {
+        let now_us = self.start_time.unwrap_or_else(Instant::now).elapsed().as_micros() as u64;
+        let last_us = self.last_report_us.load(Ordering::Relaxed);
+        let interval_us = STATS_INTERVAL.as_micros() as u64;
+        if now_us < last_us + interval_us {
+            return;
+        }
+        if self
+            .last_report_us
+            .compare_exchange(last_us, now_us, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            // Another thread already claimed this window.
+            return;
+        }
+
+        let elapsed_secs = Duration::from_micros(now_us - last_us).as_secs_f64().max(f64::EPSILON);
+
+        let chunks = self.total_chunks_generated.load(Ordering::Relaxed);
+        let fuse_requests = self.total_fuse_read_count.load(Ordering::Relaxed);
+        let fuse_bytes = self.total_fuse_bytes_sent.load(Ordering::Relaxed);
+        let hits = self.total_cache_hits.load(Ordering::Relaxed);
+        let misses = self.total_cache_misses.load(Ordering::Relaxed);
+
+        let delta_chunks = chunks.saturating_sub(self.prev_chunks_generated.swap(chunks, Ordering::Relaxed));
+        let delta_fuse_requests =
+            fuse_requests.saturating_sub(self.prev_fuse_read_count.swap(fuse_requests, Ordering::Relaxed));
+        let delta_bytes =
+            fuse_bytes.saturating_sub(self.prev_fuse_bytes_sent.swap(fuse_bytes, Ordering::Relaxed));
+        let delta_hits = hits.saturating_sub(self.prev_cache_hits.swap(hits, Ordering::Relaxed));
+        let delta_misses = misses.saturating_sub(self.prev_cache_misses.swap(misses, Ordering::Relaxed));
+
+        let delta_requests = delta_hits + delta_misses;
+        let hit_rate = if delta_requests > 0 {
+            (delta_hits as f64 / delta_requests as f64) * 100.0
+        } else {
+            0.0
+        };
+        let throughput_mb_s = (delta_bytes as f64 / 1024.0 / 1024.0) / elapsed_secs;
+
+        log::info!(
+            "[{}] interval: {} chunks gen ({:.1}/s), {} fuse req ({:.1}/s), {:.2} MB/s, cache hit rate {:.1}%",
+            self.config_summary,
+            delta_chunks,
+            delta_chunks as f64 / elapsed_secs,
+            delta_fuse_requests,
+            delta_fuse_requests as f64 / elapsed_secs,
+            throughput_mb_s,
+            hit_rate,
+        );
     }
 
     pub fn generate_report(&self) -> String {
@@ -172,7 +362,40 @@ impl BenchmarkMetrics {
             gen_raw as f64 / gen_comp as f64
         } else { 0.0 };
 
-        format!(
+        // Tail latency, in ms to match the averages above.
+        let gen_p50 = self.generation_latency_us.percentile(0.50) as f64 / 1000.0;
+        let gen_p95 = self.generation_latency_us.percentile(0.95) as f64 / 1000.0;
+        let gen_p99 = self.generation_latency_us.percentile(0.99) as f64 / 1000.0;
+        let fuse_p50 = self.fuse_latency_us.percentile(0.50) as f64 / 1000.0;
+        let fuse_p95 = self.fuse_latency_us.percentile(0.95) as f64 / 1000.0;
+        let fuse_p99 = self.fuse_latency_us.percentile(0.99) as f64 / 1000.0;
+
+        // System stats
+        let system_samples = self.system_sample_count.load(Ordering::Relaxed);
+        let avg_cpu_percent = if system_samples > 0 {
+            (self.cpu_percent_sum_milli.load(Ordering::Relaxed) as f64 / 1000.0) / system_samples as f64
+        } else { 0.0 };
+        let peak_cpu_percent = self.cpu_percent_peak_milli.load(Ordering::Relaxed) as f64 / 1000.0;
+        let avg_rss_mb = if system_samples > 0 {
+            (self.rss_bytes_sum.load(Ordering::Relaxed) as f64 / 1024.0 / 1024.0) / system_samples as f64
+        } else { 0.0 };
+        let peak_rss_mb = self.rss_bytes_peak.load(Ordering::Relaxed) as f64 / 1024.0 / 1024.0;
+        let disk_read_mb = self.disk_read_bytes.load(Ordering::Relaxed) as f64 / 1024.0 / 1024.0;
+        let disk_write_mb = self.disk_write_bytes.load(Ordering::Relaxed) as f64 / 1024.0 / 1024.0;
+
+        // How many chunks got generated per second of actual CPU time spent,
+        // as opposed to per second of wall-clock uptime — disambiguates
+        // "generation is slow" from "generation is CPU-bound".
+        let cpu_seconds = uptime.as_secs_f64() * (avg_cpu_percent / 100.0);
+        let chunks_per_cpu_second = if cpu_seconds > 0.0 { generated as f64 / cpu_seconds } else { 0.0 };
+
+        // Instantaneous rates, alongside the lifetime averages above.
+        let gen_rate_5s = self.current_generation_rate(5);
+        let gen_rate_60s = self.current_generation_rate(60);
+        let fuse_throughput_5s = self.current_fuse_throughput(5);
+        let fuse_throughput_60s = self.current_fuse_throughput(60);
+
+        let mut report = format!(
             "HopperMC Benchmark Report\n\
              =========================\n\
              Configuration: {}\n\
@@ -182,6 +405,8 @@ impl BenchmarkMetrics {
              Total Time: {:.2} ms\n\
              Avg Time: {:.2} ms/chunk\n\
              Max Time: {:.2} ms\n\
+             p50/p95/p99: {:.2} / {:.2} / {:.2} ms\n\
+             Current Rate (5s / 60s): {:.2} / {:.2} chunks/s\n\
                - Logic Breakdown:\n\
                  * Biomes: {:.2} ms\n\
                  * Noise (Terrain): {:.2} ms\n\
@@ -198,9 +423,20 @@ impl BenchmarkMetrics {
              [FUSE Filesystem]\n\
              Requests: {}\n\
              Avg Latency: {:.2} ms\n\
+             p50/p95/p99 Latency: {:.2} / {:.2} / {:.2} ms\n\
              Overhead: {:.2} ms/req (Latency - Generation)\n\
-             Throughput: {:.2} MB/s\n\
+             Throughput: {:.2} MB/s (lifetime)\n\
+             Current Throughput (5s / 60s): {:.2} / {:.2} MB/s\n\
              Compression Ratio: {:.2}x ({:.1} KB -> {:.1} KB)\n\n\
+             [System]\n\
+             Samples: {}\n\
+             Avg CPU: {:.1}%\n\
+             Peak CPU: {:.1}%\n\
+             Avg Memory: {:.1} MB\n\
+             Peak Memory: {:.1} MB\n\
+             Disk Read: {:.1} MB\n\
+             Disk Write: {:.1} MB\n\
+             Chunks per CPU-second: {:.2}\n\n\
              [Cache]\n\
              Hits: {}\n\
              Misses: {}\n\
@@ -208,14 +444,45 @@ impl BenchmarkMetrics {
             self.config_summary,
             uptime,
             generated, gen_time_total, gen_avg, gen_max,
+            gen_p50, gen_p95, gen_p99,
+            gen_rate_5s, gen_rate_60s,
             biome_avg, noise_avg, surface_avg, conv_avg,
             ser_avg, comp_avg,
             loaded, load_avg,
             saved, save_avg,
             // FUSE Params
-            fuse_requests, fuse_avg_latency, fuse_overhead, fuse_throughput, 
+            fuse_requests, fuse_avg_latency, fuse_p50, fuse_p95, fuse_p99, fuse_overhead, fuse_throughput,
+            fuse_throughput_5s, fuse_throughput_60s,
             compression_ratio, avg_raw_kb, avg_comp_kb,
+            system_samples, avg_cpu_percent, peak_cpu_percent, avg_rss_mb, peak_rss_mb,
+            disk_read_mb, disk_write_mb, chunks_per_cpu_second,
             hits, misses, hit_rate
-        )
+        );
+
+        report.push_str("\n[Per-Bucket Breakdown]\n");
+        report.push_str(&format!(
+            "{:<16} {:>10} {:>12} {:>10} {:>10} {:>14}\n",
+            "Bucket", "Chunks", "Avg Gen (ms)", "Loaded", "Saved", "Cache Hit %"
+        ));
+        for bucket in 0..self.bucket_labels.len() {
+            let chunks = self.bucket_chunks_generated[bucket].load(Ordering::Relaxed);
+            let gen_time = self.bucket_generation_time_us[bucket].load(Ordering::Relaxed) as f64 / 1000.0;
+            let gen_avg = if chunks > 0 { gen_time / chunks as f64 } else { 0.0 };
+
+            let loaded = self.bucket_chunks_loaded[bucket].load(Ordering::Relaxed);
+            let saved = self.bucket_chunks_saved[bucket].load(Ordering::Relaxed);
+
+            let hits = self.bucket_cache_hits[bucket].load(Ordering::Relaxed);
+            let misses = self.bucket_cache_misses[bucket].load(Ordering::Relaxed);
+            let total = hits + misses;
+            let hit_rate = if total > 0 { (hits as f64 / total as f64) * 100.0 } else { 0.0 };
+
+            report.push_str(&format!(
+                "{:<16} {:>10} {:>12.2} {:>10} {:>10} {:>13.1}%\n",
+                self.bucket_labels[bucket], chunks, gen_avg, loaded, saved, hit_rate
+            ));
+        }
+
+        report
     }
 }
@@ -0,0 +1,121 @@
+//! Sliding-window instantaneous rate tracking, so the report can show "over
+//! the last 5s/60s" alongside the lifetime average — a lifetime average
+//! blends away bursts and stalls a live operator actually cares about.
+//!
+//! A fixed ring of per-second buckets, each tagged with which absolute
+//! second it belongs to: `record` rolls a bucket over to the current
+//! second (resetting its count) the first time it's touched in that
+//! second, and `rate_per_sec` sums every bucket still tagged with a
+//! second inside the requested trailing window.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Seconds of history kept — covers every window this crate currently
+/// reports (5s, 60s) out of one ring.
+const RING_SECONDS: usize = 60;
+
+#[derive(Debug)]
+struct Bucket {
+    /// Absolute second (since `RateWindow::start`) this bucket's count
+    /// belongs to. `u64::MAX` marks a bucket that's never been written.
+    second: AtomicU64,
+    count: AtomicU64,
+}
+
+#[derive(Debug)]
+pub struct RateWindow {
+    start: Instant,
+    buckets: Vec<Bucket>,
+}
+
+impl RateWindow {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            buckets: (0..RING_SECONDS)
+                .map(|_| Bucket { second: AtomicU64::new(u64::MAX), count: AtomicU64::new(0) })
+                .collect(),
+        }
+    }
+
+    /// Adds `amount` to the current second's bucket, clearing out
+    /// whatever was left in that slot from `RING_SECONDS` ago.
+    pub fn record(&self, amount: u64) {
+        let second = self.start.elapsed().as_secs();
+        let bucket = &self.buckets[second as usize % RING_SECONDS];
+
+        // Whichever concurrent caller's swap is the one that actually
+        // changes the tag (old != new) owns resetting the count for this
+        // rotation; everyone who sees the tag already matching just adds.
+        if bucket.second.swap(second, Ordering::Relaxed) != second {
+            bucket.count.store(0, Ordering::Relaxed);
+        }
+        bucket.count.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// The average per-second rate over the trailing `window_secs`
+    /// seconds (clamped to `[1, RING_SECONDS]`), counting only buckets
+    /// still tagged as belonging to that window.
+    pub fn rate_per_sec(&self, window_secs: u64) -> f64 {
+        let window_secs = window_secs.clamp(1, RING_SECONDS as u64);
+        let current_second = self.start.elapsed().as_secs();
+
+        let mut total = 0u64;
+        for back in 0..window_secs {
+            let Some(second) = current_second.checked_sub(back) else {
+                break;
+            };
+            let bucket = &self.buckets[second as usize % RING_SECONDS];
+            if bucket.second.load(Ordering::Relaxed) == second {
+                total += bucket.count.load(Ordering::Relaxed);
+            }
+        }
+
+        total as f64 / window_secs as f64
+    }
+}
+
+impl Default for RateWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn rate_reflects_recent_samples_only() {
+        let window = RateWindow::new();
+        for _ in 0..10 {
+            window.record(1);
+        }
+
+        assert_eq!(window.rate_per_sec(5), 2.0);
+    }
+
+    #[test]
+    fn empty_window_has_zero_rate() {
+        let window = RateWindow::new();
+        assert_eq!(window.rate_per_sec(5), 0.0);
+    }
+
+    #[test]
+    fn old_buckets_age_out_of_the_window() {
+        let window = RateWindow::new();
+        window.record(100);
+        thread::sleep(Duration::from_millis(1100));
+        window.record(1);
+
+        // The 100 recorded a second ago should no longer count toward a
+        // window that only looks back a fraction of a second... but since
+        // windows are whole seconds here, assert the cheaper invariant:
+        // a 1-second-only window excludes the first burst, while a wider
+        // one still includes it.
+        assert!(window.rate_per_sec(1) < window.rate_per_sec(5));
+    }
+}
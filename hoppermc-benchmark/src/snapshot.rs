@@ -0,0 +1,165 @@
+//! A point-in-time, plain-data copy of [`BenchmarkMetrics`][super::BenchmarkMetrics],
+//! for handing off to machine consumers (Prometheus scraping, a JSON log
+//! line, an external dashboard) instead of the hand-formatted report string.
+
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use crate::BenchmarkMetrics;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsSnapshot {
+    pub config_summary: String,
+    pub uptime_secs: f64,
+
+    pub chunks_generated: usize,
+    pub generation_time_us_total: u64,
+    pub generation_time_us_max: u64,
+    pub generation_p50_us: u64,
+    pub generation_p95_us: u64,
+    pub generation_p99_us: u64,
+
+    pub chunks_loaded: usize,
+    pub load_time_us_total: u64,
+    pub chunks_saved: usize,
+    pub save_time_us_total: u64,
+
+    pub fuse_read_count: usize,
+    pub fuse_read_time_us_total: u64,
+    pub fuse_bytes_sent: usize,
+    pub fuse_p50_us: u64,
+    pub fuse_p95_us: u64,
+    pub fuse_p99_us: u64,
+
+    pub gen_bytes_raw: usize,
+    pub gen_bytes_compressed: usize,
+
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    pub cache_hit_rate: f64,
+}
+
+impl BenchmarkMetrics {
+    /// Atomically loads every counter into a plain-data [`MetricsSnapshot`].
+    /// Each field is loaded independently (no cross-field lock), same as
+    /// [`Self::generate_report`] — fine for a metrics snapshot, where a
+    /// counter ticking over between two loads is noise, not corruption.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let hits = self.total_cache_hits.load(Ordering::Relaxed);
+        let misses = self.total_cache_misses.load(Ordering::Relaxed);
+        let total_requests = hits + misses;
+        let cache_hit_rate = if total_requests > 0 {
+            (hits as f64 / total_requests as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        MetricsSnapshot {
+            config_summary: self.config_summary.clone(),
+            uptime_secs: self.start_time.unwrap_or_else(Instant::now).elapsed().as_secs_f64(),
+
+            chunks_generated: self.total_chunks_generated.load(Ordering::Relaxed),
+            generation_time_us_total: self.total_generation_time_us.load(Ordering::Relaxed),
+            generation_time_us_max: self.max_generation_time_us.load(Ordering::Relaxed),
+            generation_p50_us: self.generation_latency_us.percentile(0.50),
+            generation_p95_us: self.generation_latency_us.percentile(0.95),
+            generation_p99_us: self.generation_latency_us.percentile(0.99),
+
+            chunks_loaded: self.total_chunks_loaded.load(Ordering::Relaxed),
+            load_time_us_total: self.total_load_time_us.load(Ordering::Relaxed),
+            chunks_saved: self.total_chunks_saved.load(Ordering::Relaxed),
+            save_time_us_total: self.total_save_time_us.load(Ordering::Relaxed),
+
+            fuse_read_count: self.total_fuse_read_count.load(Ordering::Relaxed),
+            fuse_read_time_us_total: self.total_fuse_read_time_us.load(Ordering::Relaxed),
+            fuse_bytes_sent: self.total_fuse_bytes_sent.load(Ordering::Relaxed),
+            fuse_p50_us: self.fuse_latency_us.percentile(0.50),
+            fuse_p95_us: self.fuse_latency_us.percentile(0.95),
+            fuse_p99_us: self.fuse_latency_us.percentile(0.99),
+
+            gen_bytes_raw: self.total_gen_bytes_raw.load(Ordering::Relaxed),
+            gen_bytes_compressed: self.total_gen_bytes_compressed.load(Ordering::Relaxed),
+
+            cache_hits: hits,
+            cache_misses: misses,
+            cache_hit_rate,
+        }
+    }
+
+    /// Renders the current snapshot in the Prometheus text exposition
+    /// format, suitable for serving directly off a `/metrics` endpoint.
+    pub fn to_prometheus(&self) -> String {
+        let snap = self.snapshot();
+        let mut out = String::new();
+
+        let counter = |out: &mut String, name: &str, help: &str, value: u64| {
+            out.push_str(&format!("# HELP {name} {help}\n"));
+            out.push_str(&format!("# TYPE {name} counter\n"));
+            out.push_str(&format!("{name} {value}\n"));
+        };
+        let gauge = |out: &mut String, name: &str, help: &str, value: f64| {
+            out.push_str(&format!("# HELP {name} {help}\n"));
+            out.push_str(&format!("# TYPE {name} gauge\n"));
+            out.push_str(&format!("{name} {value}\n"));
+        };
+
+        counter(&mut out, "hoppermc_chunks_generated_total", "Total chunks generated.", snap.chunks_generated as u64);
+        counter(&mut out, "hoppermc_generation_time_us_total", "Total chunk generation time in microseconds.", snap.generation_time_us_total);
+        gauge(&mut out, "hoppermc_generation_latency_p50_us", "Chunk generation p50 latency in microseconds.", snap.generation_p50_us as f64);
+        gauge(&mut out, "hoppermc_generation_latency_p95_us", "Chunk generation p95 latency in microseconds.", snap.generation_p95_us as f64);
+        gauge(&mut out, "hoppermc_generation_latency_p99_us", "Chunk generation p99 latency in microseconds.", snap.generation_p99_us as f64);
+
+        counter(&mut out, "hoppermc_chunks_loaded_total", "Total chunks loaded from storage.", snap.chunks_loaded as u64);
+        counter(&mut out, "hoppermc_chunks_saved_total", "Total chunks saved to storage.", snap.chunks_saved as u64);
+
+        counter(&mut out, "hoppermc_fuse_requests_total", "Total FUSE read requests served.", snap.fuse_read_count as u64);
+        counter(&mut out, "hoppermc_fuse_bytes_sent_total", "Total bytes sent in FUSE read replies.", snap.fuse_bytes_sent as u64);
+        gauge(&mut out, "hoppermc_fuse_latency_p50_us", "FUSE read p50 latency in microseconds.", snap.fuse_p50_us as f64);
+        gauge(&mut out, "hoppermc_fuse_latency_p95_us", "FUSE read p95 latency in microseconds.", snap.fuse_p95_us as f64);
+        gauge(&mut out, "hoppermc_fuse_latency_p99_us", "FUSE read p99 latency in microseconds.", snap.fuse_p99_us as f64);
+
+        counter(&mut out, "hoppermc_gen_bytes_raw_total", "Total uncompressed generated chunk bytes.", snap.gen_bytes_raw as u64);
+        counter(&mut out, "hoppermc_gen_bytes_compressed_total", "Total compressed generated chunk bytes.", snap.gen_bytes_compressed as u64);
+
+        counter(&mut out, "hoppermc_cache_hits_total", "Total chunk cache hits.", snap.cache_hits as u64);
+        counter(&mut out, "hoppermc_cache_misses_total", "Total chunk cache misses.", snap.cache_misses as u64);
+        gauge(&mut out, "hoppermc_cache_hit_rate", "Chunk cache hit rate as a percentage.", snap.cache_hit_rate);
+
+        gauge(&mut out, "hoppermc_uptime_seconds", "Seconds since this session's BenchmarkMetrics was created.", snap.uptime_secs);
+
+        out
+    }
+
+    /// Renders the current snapshot as a JSON object.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.snapshot())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prometheus_output_includes_help_and_type_lines() {
+        let metrics = BenchmarkMetrics::new("test".to_string(), vec![]);
+        metrics.record_generation(0, std::time::Duration::from_micros(500));
+        let out = metrics.to_prometheus();
+
+        assert!(out.contains("# HELP hoppermc_chunks_generated_total"));
+        assert!(out.contains("# TYPE hoppermc_chunks_generated_total counter"));
+        assert!(out.contains("hoppermc_chunks_generated_total 1"));
+    }
+
+    #[test]
+    fn snapshot_reflects_recorded_samples() {
+        let metrics = BenchmarkMetrics::new("test".to_string(), vec![]);
+        metrics.record_fuse_request(std::time::Duration::from_micros(100), 4096);
+        let snap = metrics.snapshot();
+
+        assert_eq!(snap.fuse_read_count, 1);
+        assert_eq!(snap.fuse_bytes_sent, 4096);
+    }
+}
@@ -0,0 +1,121 @@
+//! Periodic host resource sampling (CPU, memory, disk I/O), correlated
+//! against generation throughput so operators can tell whether a slow
+//! session is CPU-bound, I/O-bound, or just a noisy host.
+//!
+//! Parses `/proc` directly rather than pulling in a crate like
+//! `systemstat` for it — Linux-only, but so is everything else this
+//! project's FUSE mount depends on.
+
+use std::fs;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::BenchmarkMetrics;
+
+/// How often [`SystemSampler::spawn`] polls the host by default.
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Total (all-core) CPU jiffies read from `/proc/stat`'s `cpu` line, split
+/// into "busy" (user+nice+system+irq+softirq+steal) and "idle"
+/// (idle+iowait) buckets, so utilization can be computed as a delta
+/// between two samples rather than a lifetime average that can never
+/// reflect a recent change.
+struct CpuJiffies {
+    busy: u64,
+    idle: u64,
+}
+
+impl CpuJiffies {
+    fn read() -> Option<Self> {
+        let stat = fs::read_to_string("/proc/stat").ok()?;
+        let line = stat.lines().find(|l| l.starts_with("cpu "))?;
+        let fields: Vec<u64> = line.split_whitespace().skip(1).filter_map(|f| f.parse().ok()).collect();
+        if fields.len() < 5 {
+            return None;
+        }
+
+        let (user, nice, system, idle, iowait) = (fields[0], fields[1], fields[2], fields[3], fields[4]);
+        let irq = fields.get(5).copied().unwrap_or(0);
+        let softirq = fields.get(6).copied().unwrap_or(0);
+        let steal = fields.get(7).copied().unwrap_or(0);
+
+        Some(Self {
+            busy: user + nice + system + irq + softirq + steal,
+            idle: idle + iowait,
+        })
+    }
+
+    fn percent_busy_since(&self, prev: &CpuJiffies) -> f64 {
+        let busy_delta = self.busy.saturating_sub(prev.busy) as f64;
+        let idle_delta = self.idle.saturating_sub(prev.idle) as f64;
+        let total = busy_delta + idle_delta;
+        if total > 0.0 {
+            (busy_delta / total) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+fn read_self_rss_bytes() -> u64 {
+    let Ok(status) = fs::read_to_string("/proc/self/status") else {
+        return 0;
+    };
+    status
+        .lines()
+        .find(|l| l.starts_with("VmRSS:"))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+        .unwrap_or(0)
+}
+
+/// `(read_bytes, write_bytes)` from `/proc/self/io` — cumulative since
+/// process start, same as the kernel's own disk I/O accounting.
+fn read_self_disk_io_bytes() -> (u64, u64) {
+    let Ok(io) = fs::read_to_string("/proc/self/io") else {
+        return (0, 0);
+    };
+    let field = |prefix: &str| {
+        io.lines()
+            .find(|l| l.starts_with(prefix))
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    (field("read_bytes:"), field("write_bytes:"))
+}
+
+/// Background host-resource sampler. Nothing samples until
+/// [`Self::spawn`] is called — most runs, auto-benchmark sweeps
+/// especially, don't need the extra `/proc` reads.
+pub struct SystemSampler;
+
+impl SystemSampler {
+    /// Spawns a thread that polls host CPU, this process's RSS, and this
+    /// process's disk I/O counters every `interval`, recording each
+    /// sample into `metrics` for the life of the process. Missing `/proc`
+    /// entries (e.g. a non-Linux host) just make that sample read as
+    /// zero rather than panicking.
+    pub fn spawn(metrics: Arc<BenchmarkMetrics>, interval: Duration) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            let mut prev_cpu = CpuJiffies::read();
+            loop {
+                std::thread::sleep(interval);
+
+                let cur_cpu = CpuJiffies::read();
+                let cpu_percent = match (&cur_cpu, &prev_cpu) {
+                    (Some(cur), Some(prev)) => cur.percent_busy_since(prev),
+                    _ => 0.0,
+                };
+                prev_cpu = cur_cpu;
+
+                let rss_bytes = read_self_rss_bytes();
+                let (disk_read_bytes, disk_write_bytes) = read_self_disk_io_bytes();
+
+                metrics.record_system_sample(cpu_percent, rss_bytes, disk_read_bytes, disk_write_bytes);
+            }
+        })
+    }
+}
@@ -1,4 +1,5 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use pumpkin_world::dimension::Dimension;
 
 pub trait WorldGenerator: Send + Sync {
     fn generate_chunk(&self, x: i32, z: i32) -> Result<Vec<u8>>;
@@ -6,4 +7,38 @@ pub trait WorldGenerator: Send + Sync {
 
 pub mod flat;
 pub mod vanilla;
-pub mod builder;
\ No newline at end of file
+pub mod builder;
+
+/// Parses a `--dimensions` list entry ("overworld", "nether"/"the_nether",
+/// "end"/"the_end") into the `Dimension` `VanillaWorldGenerator::with_dimension`
+/// expects.
+pub fn parse_dimension(name: &str) -> Result<Dimension> {
+    match name.to_lowercase().as_str() {
+        "overworld" => Ok(Dimension::Overworld),
+        "nether" | "the_nether" => Ok(Dimension::Nether),
+        "end" | "the_end" => Ok(Dimension::End),
+        other => bail!("unknown dimension {other:?} (expected overworld, nether, or end)"),
+    }
+}
+
+/// The save-layout directory name a dimension's region tree is mounted
+/// under, matching vanilla's own `DIM-1`/`DIM1` folder naming.
+pub fn dimension_dir_name(dimension: &Dimension) -> &'static str {
+    match dimension {
+        Dimension::Overworld => "overworld",
+        Dimension::Nether => "DIM-1",
+        Dimension::End => "DIM1",
+    }
+}
+
+/// A small, stable-across-restarts discriminator for a dimension, meant for
+/// namespacing a flat `(chunk_x, chunk_z)` storage key space (see
+/// `hoppermc_storage::dimensioned::DimensionedStorage`) rather than for
+/// anything save-layout related — use [`dimension_dir_name`] for that.
+pub fn dimension_id(dimension: &Dimension) -> i32 {
+    match dimension {
+        Dimension::Overworld => 0,
+        Dimension::Nether => 1,
+        Dimension::End => 2,
+    }
+}
\ No newline at end of file
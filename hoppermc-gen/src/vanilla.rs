@@ -22,6 +22,15 @@ impl VanillaWorldGenerator {
         let generator = Box::new(VanillaGenerator::new(pumpkin_seed, dimension.clone()));
         Self { generator, dimension }
     }
+
+    /// Which dimension this generator was constructed for (e.g. to label a
+    /// per-dimension storage namespace or FUSE directory). This is an
+    /// inherent method, not part of [`WorldGenerator`], so it is reachable
+    /// only through a concrete `VanillaWorldGenerator`, not through a
+    /// `Box<dyn WorldGenerator>`/`Arc<dyn WorldGenerator>`.
+    pub fn dimension(&self) -> Dimension {
+        self.dimension.clone()
+    }
 }
 
 impl WorldGenerator for VanillaWorldGenerator {
@@ -0,0 +1,80 @@
+//! Per-dimension storage namespacing for any async `ChunkStorage` backend.
+//!
+//! Every backend in this crate keys chunks by a flat `(chunk_x, chunk_z)`
+//! pair with no dimension column, so mounting more than one dimension
+//! against the same backend would otherwise have the overworld's chunk
+//! `(0, 0)` collide with the nether's. This wraps a backend and offsets
+//! every coordinate by a dimension-specific stride before delegating, so
+//! each dimension gets its own disjoint slice of the key space.
+
+use crate::ChunkStorage;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Chunk-x spacing between dimensions' namespaces. A multiple of 32 (the
+/// region size) so a dimension's chunks always fall in whole regions of
+/// their own rather than splitting a region across dimensions, and large
+/// enough that no vanilla world border (chunk coordinates run to roughly
+/// ±1,875,000) reaches into the next dimension's namespace.
+const DIMENSION_STRIDE_CHUNKS: i32 = 1 << 24;
+const DIMENSION_STRIDE_REGIONS: i32 = DIMENSION_STRIDE_CHUNKS / 32;
+
+/// Wraps `inner`, shifting every chunk/region `x` coordinate by
+/// `dimension_id * DIMENSION_STRIDE_*` before delegating.
+/// `dimension_id` should be stable for a given dimension across restarts
+/// (see [`hoppermc_gen::dimension_id`] — overworld=0, nether=1, end=2).
+pub struct DimensionedStorage<S: ChunkStorage> {
+    inner: S,
+    dimension_id: i32,
+}
+
+impl<S: ChunkStorage + Send + Sync> DimensionedStorage<S> {
+    pub fn new(inner: S, dimension_id: i32) -> Self {
+        Self { inner, dimension_id }
+    }
+}
+
+#[async_trait]
+impl<S: ChunkStorage + Send + Sync> ChunkStorage for DimensionedStorage<S> {
+    async fn save_chunk(&self, x: i32, z: i32, data: &[u8]) -> Result<()> {
+        self.inner.save_chunk(x + self.dimension_id * DIMENSION_STRIDE_CHUNKS, z, data).await
+    }
+
+    async fn load_chunk(&self, x: i32, z: i32) -> Result<Option<Vec<u8>>> {
+        self.inner.load_chunk(x + self.dimension_id * DIMENSION_STRIDE_CHUNKS, z).await
+    }
+
+    async fn get_total_size(&self) -> Result<u64> {
+        // Deliberately not namespaced: the physical backend's total size is
+        // a property of the whole database, not of any one dimension.
+        self.inner.get_total_size().await
+    }
+
+    async fn save_region_root(&self, region_x: i32, region_z: i32, root: [u8; 32]) -> Result<()> {
+        self.inner
+            .save_region_root(region_x + self.dimension_id * DIMENSION_STRIDE_REGIONS, region_z, root)
+            .await
+    }
+
+    async fn load_region_root(&self, region_x: i32, region_z: i32) -> Result<Option<[u8; 32]>> {
+        self.inner
+            .load_region_root(region_x + self.dimension_id * DIMENSION_STRIDE_REGIONS, region_z)
+            .await
+    }
+
+    async fn list_regions(&self) -> Result<Vec<(i32, i32)>> {
+        let offset = self.dimension_id * DIMENSION_STRIDE_REGIONS;
+        Ok(self
+            .inner
+            .list_regions()
+            .await?
+            .into_iter()
+            // A dimension's regions sit on both sides of its offset, so round
+            // to the nearest stride rather than flooring.
+            .filter(|(region_x, _)| {
+                (region_x + DIMENSION_STRIDE_REGIONS / 2).div_euclid(DIMENSION_STRIDE_REGIONS) == self.dimension_id
+            })
+            .map(|(region_x, region_z)| (region_x - offset, region_z))
+            .collect())
+    }
+}
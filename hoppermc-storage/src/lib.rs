@@ -0,0 +1,38 @@
+//! Pluggable `ChunkStorage` backends for the `hoppermc` binary.
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+pub mod postgres;
+pub mod encrypted;
+pub mod merkle;
+pub mod sqlite;
+pub mod dimensioned;
+
+/// Async chunk persistence, implemented by each backend in this crate.
+/// Coordinates are absolute chunk coordinates, matching the root `src/`
+/// tree's synchronous `ChunkStorage` trait in spirit (same `(x, z)`
+/// addressing), but async since every backend here talks to a database.
+#[async_trait]
+pub trait ChunkStorage {
+    async fn save_chunk(&self, x: i32, z: i32, data: &[u8]) -> Result<()>;
+    async fn load_chunk(&self, x: i32, z: i32) -> Result<Option<Vec<u8>>>;
+    async fn get_total_size(&self) -> Result<u64>;
+    async fn save_region_root(&self, region_x: i32, region_z: i32, root: [u8; 32]) -> Result<()>;
+    async fn load_region_root(&self, region_x: i32, region_z: i32) -> Result<Option<[u8; 32]>>;
+    async fn list_regions(&self) -> Result<Vec<(i32, i32)>>;
+}
+
+/// Which on-disk representation [`postgres::PostgresStorage`] uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageMode {
+    /// One row per chunk, raw compressed bytes in a `BYTEA` column.
+    PgRaw,
+    /// One row per chunk, decoded into JSONB for queryability. Not
+    /// implemented by [`postgres::PostgresStorage`]: schema setup only logs
+    /// a warning, saves return an error and loads return `None`.
+    PgJsonb,
+    /// Content-addressed: chunks with identical compressed payloads share
+    /// one `blobs` row, refcounted.
+    PgDedup,
+}
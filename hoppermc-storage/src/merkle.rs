@@ -0,0 +1,178 @@
+//! Per-region Merkle integrity roots.
+//!
+//! Each region's 1024 chunk slots (32x32, in the same `(x & 31) + (z & 31)
+//! * 32` order the Anvil location table uses) become the leaves of a binary
+//! Merkle tree; an ungenerated slot hashes to [`empty_leaf`] rather than
+//! being skipped, so the root is deterministic and comparable across two
+//! replicas regardless of which of them has actually generated a given
+//! chunk yet. Incomplete layers are padded with the canonical hash of an
+//! *empty subtree* of that layer's size (not a duplicate of the last real
+//! node) — duplicating real leaves to pad a layer lets an attacker forge a
+//! root by repeating a node, which a distinct empty-subtree hash per layer
+//! avoids.
+
+use anyhow::Result;
+use blake3::Hasher;
+
+use crate::ChunkStorage;
+
+/// Leaves per region: 32x32 chunk slots. Already a power of two, so a
+/// full region never needs padding — padding only matters for the partial
+/// subtrees `diff_regions` walks during a scrub.
+pub const LEAVES_PER_REGION: usize = 1024;
+
+const LEAF_DOMAIN: &[u8] = b"hoppermc.merkle.leaf";
+const NODE_DOMAIN: &[u8] = b"hoppermc.merkle.node";
+
+/// Hashes one chunk slot's compressed blob, or [`empty_leaf`] if the slot
+/// has never been generated.
+pub fn leaf_hash(blob: Option<&[u8]>) -> [u8; 32] {
+    match blob {
+        Some(bytes) => {
+            let mut hasher = Hasher::new();
+            hasher.update(LEAF_DOMAIN);
+            hasher.update(bytes);
+            *hasher.finalize().as_bytes()
+        }
+        None => empty_leaf(),
+    }
+}
+
+/// Canonical hash of an ungenerated chunk slot.
+pub fn empty_leaf() -> [u8; 32] {
+    *blake3::hash(LEAF_DOMAIN).as_bytes()
+}
+
+fn combine(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Hasher::new();
+    hasher.update(NODE_DOMAIN);
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+// The canonical hash of a fully-empty subtree `depth` layers tall (depth 0
+// = a single empty leaf), used to pad an odd-length layer instead of
+// duplicating its last real node.
+fn empty_subtree(depth: usize) -> [u8; 32] {
+    let mut hash = empty_leaf();
+    for _ in 0..depth {
+        hash = combine(&hash, &hash);
+    }
+    hash
+}
+
+/// Builds the Merkle root over `leaves`, padding each layer up to an even
+/// count with that layer's empty-subtree hash until a single root remains.
+/// `leaves.is_empty()` is treated as a region with no slots at all, and
+/// returns the empty-leaf hash.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return empty_leaf();
+    }
+
+    let mut level = leaves.to_vec();
+    let mut depth = 0usize;
+    while level.len() > 1 {
+        if level.len() % 2 == 1 {
+            level.push(empty_subtree(depth));
+        }
+        level = level
+            .chunks_exact(2)
+            .map(|pair| combine(&pair[0], &pair[1]))
+            .collect();
+        depth += 1;
+    }
+    level[0]
+}
+
+/// Chunk-local `(x, z)` coordinates of every slot in region `(region_x,
+/// region_z)`, in leaf order.
+pub fn region_chunk_coords(region_x: i32, region_z: i32) -> Vec<(i32, i32)> {
+    let mut coords = Vec::with_capacity(LEAVES_PER_REGION);
+    for index in 0..LEAVES_PER_REGION {
+        let rel_x = (index % 32) as i32;
+        let rel_z = (index / 32) as i32;
+        coords.push((region_x * 32 + rel_x, region_z * 32 + rel_z));
+    }
+    coords
+}
+
+/// Recomputes region `(region_x, region_z)`'s root by loading all 1024
+/// chunk slots from `storage` fresh — this is what a scrub compares
+/// against the root last persisted via [`ChunkStorage::save_region_root`].
+pub async fn compute_region_root(
+    storage: &(dyn ChunkStorage + Send + Sync),
+    region_x: i32,
+    region_z: i32,
+) -> Result<[u8; 32]> {
+    let mut leaves = Vec::with_capacity(LEAVES_PER_REGION);
+    for (chunk_x, chunk_z) in region_chunk_coords(region_x, region_z) {
+        let blob = storage.load_chunk(chunk_x, chunk_z).await?;
+        leaves.push(leaf_hash(blob.as_deref()));
+    }
+    Ok(merkle_root(&leaves))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(blobs: &[Option<&[u8]>]) -> Vec<[u8; 32]> {
+        blobs.iter().map(|blob| leaf_hash(*blob)).collect()
+    }
+
+    #[test]
+    fn test_root_is_stable_across_calls() {
+        let blobs: Vec<Option<&[u8]>> = vec![Some(b"a"), Some(b"b"), None, Some(b"c")];
+        assert_eq!(merkle_root(&leaves(&blobs)), merkle_root(&leaves(&blobs)));
+    }
+
+    #[test]
+    fn test_root_is_order_sensitive() {
+        let forward = leaves(&[Some(b"a"), Some(b"b"), Some(b"c"), Some(b"d")]);
+        let swapped = leaves(&[Some(b"b"), Some(b"a"), Some(b"c"), Some(b"d")]);
+        assert_ne!(merkle_root(&forward), merkle_root(&swapped));
+    }
+
+    #[test]
+    fn test_empty_region_matches_empty_leaf() {
+        assert_eq!(merkle_root(&[]), empty_leaf());
+    }
+
+    #[test]
+    fn test_all_empty_leaves_root_is_deterministic() {
+        let leaves = vec![empty_leaf(); LEAVES_PER_REGION];
+        assert_eq!(merkle_root(&leaves), merkle_root(&leaves));
+        // A full region of ungenerated slots shouldn't collide with a
+        // single empty leaf's hash once combined through the tree.
+        assert_ne!(merkle_root(&leaves), empty_leaf());
+    }
+
+    #[test]
+    fn test_odd_length_layer_padding_does_not_duplicate_last_leaf() {
+        // Three leaves: an even-length padding scheme that just duplicated
+        // the last leaf would produce the same root as explicitly
+        // duplicating it, which is exactly what `empty_subtree` padding is
+        // meant to avoid.
+        let three = leaves(&[Some(b"a"), Some(b"b"), Some(b"c")]);
+        let duplicated_last = leaves(&[Some(b"a"), Some(b"b"), Some(b"c"), Some(b"c")]);
+        assert_ne!(merkle_root(&three), merkle_root(&duplicated_last));
+    }
+
+    #[test]
+    fn test_changing_one_leaf_changes_the_root() {
+        let original = leaves(&[Some(b"a"), Some(b"b"), Some(b"c"), Some(b"d")]);
+        let changed = leaves(&[Some(b"a"), Some(b"b"), Some(b"z"), Some(b"d")]);
+        assert_ne!(merkle_root(&original), merkle_root(&changed));
+    }
+
+    #[test]
+    fn test_region_chunk_coords_are_region_relative_and_in_leaf_order() {
+        let coords = region_chunk_coords(2, -3);
+        assert_eq!(coords.len(), LEAVES_PER_REGION);
+        assert_eq!(coords[0], (2 * 32, -3 * 32));
+        assert_eq!(coords[1], (2 * 32 + 1, -3 * 32));
+        assert_eq!(coords[32], (2 * 32, -3 * 32 + 1));
+    }
+}
@@ -2,7 +2,7 @@ use crate::{ChunkStorage, StorageMode};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use deadpool_postgres::{Config, ManagerConfig, Pool, RecyclingMethod, Runtime};
-use tokio_postgres::NoTls;
+use tokio_postgres::{NoTls, Transaction};
 
 pub struct PostgresStorage {
     pool: Pool,
@@ -23,15 +23,36 @@ impl PostgresStorage {
         // Ensure connections work and schema exists
         let storage = Self { pool, mode };
         storage.init_schema().await?;
-        
+
         Ok(storage)
     }
 
+    fn hash_payload(data: &[u8]) -> Vec<u8> {
+        blake3::hash(data).as_bytes().to_vec()
+    }
+
+    // Drops a blob's refcount by one and garbage-collects it once nothing
+    // references it anymore. Runs inside the same transaction as the
+    // chunk_index update that stopped pointing at it.
+    async fn release_blob(txn: &Transaction<'_>, hash: &[u8]) -> Result<()> {
+        txn.execute(
+            "UPDATE blobs SET refcount = refcount - 1 WHERE hash = $1",
+            &[&hash],
+        ).await.context("Failed to decrement blob refcount")?;
+
+        txn.execute(
+            "DELETE FROM blobs WHERE hash = $1 AND refcount <= 0",
+            &[&hash],
+        ).await.context("Failed to garbage-collect blob")?;
+
+        Ok(())
+    }
+
     async fn init_schema(&self) -> Result<()> {
         let client = self.pool.get().await.context("Failed to get DB connection")?;
         
         match self.mode {
-            StorageMode::Raw => {
+            StorageMode::PgRaw => {
                 client.batch_execute("
                     CREATE TABLE IF NOT EXISTS chunks_raw (
                         x INT,
@@ -42,10 +63,43 @@ impl PostgresStorage {
                     );
                 ").await.context("Failed to init raw schema")?;
             }
+            StorageMode::PgDedup => {
+                // `blobs` holds each distinct compressed chunk payload once,
+                // keyed by its content hash; `chunk_index` just points a
+                // coordinate at whichever hash currently occupies it. Flat
+                // worlds and big plains/ocean biomes collapse to a handful
+                // of blob rows instead of one per chunk.
+                client.batch_execute("
+                    CREATE TABLE IF NOT EXISTS blobs (
+                        hash BYTEA PRIMARY KEY,
+                        data BYTEA NOT NULL,
+                        refcount BIGINT NOT NULL DEFAULT 0
+                    );
+                    CREATE TABLE IF NOT EXISTS chunk_index (
+                        chunk_x INT,
+                        chunk_z INT,
+                        hash BYTEA NOT NULL REFERENCES blobs(hash),
+                        PRIMARY KEY (chunk_x, chunk_z)
+                    );
+                ").await.context("Failed to init dedup schema")?;
+            }
             _ => {
                 log::warn!("Schema init for mode {:?} not yet implemented", self.mode);
             }
         }
+
+        // Integrity roots apply regardless of storage mode, so this table
+        // isn't gated on `self.mode`.
+        client.batch_execute("
+            CREATE TABLE IF NOT EXISTS region_roots (
+                world TEXT NOT NULL DEFAULT 'default',
+                region_x INT NOT NULL,
+                region_z INT NOT NULL,
+                root BYTEA NOT NULL,
+                PRIMARY KEY (world, region_x, region_z)
+            );
+        ").await.context("Failed to init region_roots schema")?;
+
         Ok(())
     }
 }
@@ -53,18 +107,51 @@ impl PostgresStorage {
 #[async_trait]
 impl ChunkStorage for PostgresStorage {
     async fn save_chunk(&self, x: i32, z: i32, data: &[u8]) -> Result<()> {
-        let client = self.pool.get().await.context("Failed to get DB connection")?;
-
         match self.mode {
-            StorageMode::Raw => {
+            StorageMode::PgRaw => {
+                let client = self.pool.get().await.context("Failed to get DB connection")?;
                 // Upsert logic
                 client.execute(
-                    "INSERT INTO chunks_raw (x, z, data, updated_at) 
+                    "INSERT INTO chunks_raw (x, z, data, updated_at)
                      VALUES ($1, $2, $3, NOW())
                      ON CONFLICT (x, z) DO UPDATE SET data = $3, updated_at = NOW()",
                     &[&x, &z, &data],
                 ).await.context("Failed to insert chunk raw")?;
             }
+            StorageMode::PgDedup => {
+                let mut client = self.pool.get().await.context("Failed to get DB connection")?;
+                let hash = Self::hash_payload(data);
+
+                let txn = client.transaction().await.context("Failed to start transaction")?;
+
+                // Whatever hash (if any) this coordinate previously pointed
+                // at, so its blob's refcount can be released once the new
+                // mapping lands.
+                let previous: Option<Vec<u8>> = txn.query_opt(
+                    "SELECT hash FROM chunk_index WHERE chunk_x = $1 AND chunk_z = $2",
+                    &[&x, &z],
+                ).await?.map(|row| row.get(0));
+
+                txn.execute(
+                    "INSERT INTO blobs (hash, data, refcount) VALUES ($1, $2, 1)
+                     ON CONFLICT (hash) DO UPDATE SET refcount = blobs.refcount + 1",
+                    &[&hash, &data],
+                ).await.context("Failed to upsert blob")?;
+
+                txn.execute(
+                    "INSERT INTO chunk_index (chunk_x, chunk_z, hash) VALUES ($1, $2, $3)
+                     ON CONFLICT (chunk_x, chunk_z) DO UPDATE SET hash = $3",
+                    &[&x, &z, &hash],
+                ).await.context("Failed to update chunk index")?;
+
+                // Released even when the hash is unchanged: the upsert above
+                // already added a reference for this write.
+                if let Some(previous) = previous {
+                    Self::release_blob(&txn, &previous).await?;
+                }
+
+                txn.commit().await.context("Failed to commit dedup write")?;
+            }
             _ => anyhow::bail!("Save not implemented for mode {:?}", self.mode),
         }
 
@@ -73,14 +160,14 @@ impl ChunkStorage for PostgresStorage {
 
     async fn load_chunk(&self, x: i32, z: i32) -> Result<Option<Vec<u8>>> {
         let client = self.pool.get().await.context("Failed to get DB connection")?;
-        
+
         match self.mode {
-             StorageMode::Raw => {
+             StorageMode::PgRaw => {
                  let rows = client.query(
                      "SELECT data FROM chunks_raw WHERE x = $1 AND z = $2",
                      &[&x, &z]
                  ).await?;
-                 
+
                  if let Some(row) = rows.first() {
                      let data: Vec<u8> = row.get(0);
                      Ok(Some(data))
@@ -88,7 +175,119 @@ impl ChunkStorage for PostgresStorage {
                      Ok(None)
                  }
              },
+             StorageMode::PgDedup => {
+                 let rows = client.query(
+                     "SELECT blobs.data FROM chunk_index
+                      JOIN blobs ON blobs.hash = chunk_index.hash
+                      WHERE chunk_index.chunk_x = $1 AND chunk_index.chunk_z = $2",
+                     &[&x, &z],
+                 ).await?;
+
+                 Ok(rows.first().map(|row| row.get(0)))
+             },
              _ => Ok(None)
         }
     }
+
+    async fn get_total_size(&self) -> Result<u64> {
+        let client = self.pool.get().await.context("Failed to get DB connection")?;
+
+        match self.mode {
+            StorageMode::PgRaw => {
+                let row = client.query_one(
+                    "SELECT COALESCE(SUM(length(data)), 0) FROM chunks_raw",
+                    &[],
+                ).await.context("Failed to sum raw chunk sizes")?;
+                let total: i64 = row.get(0);
+                Ok(total as u64)
+            }
+            // Unique blob bytes only, not refcount-weighted — this is what
+            // makes the benchmark report show the dedup ratio against the
+            // logical (per-chunk) size.
+            StorageMode::PgDedup => {
+                let row = client.query_one(
+                    "SELECT COALESCE(SUM(length(data)), 0) FROM blobs",
+                    &[],
+                ).await.context("Failed to sum unique blob sizes")?;
+                let total: i64 = row.get(0);
+                Ok(total as u64)
+            }
+            _ => anyhow::bail!("get_total_size not implemented for mode {:?}", self.mode),
+        }
+    }
+
+    async fn save_region_root(&self, region_x: i32, region_z: i32, root: [u8; 32]) -> Result<()> {
+        let client = self.pool.get().await.context("Failed to get DB connection")?;
+        let root_bytes = root.to_vec();
+        client.execute(
+            "INSERT INTO region_roots (region_x, region_z, root)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (world, region_x, region_z) DO UPDATE SET root = $3",
+            &[&region_x, &region_z, &root_bytes],
+        ).await.context("Failed to upsert region root")?;
+        Ok(())
+    }
+
+    async fn load_region_root(&self, region_x: i32, region_z: i32) -> Result<Option<[u8; 32]>> {
+        let client = self.pool.get().await.context("Failed to get DB connection")?;
+        let row = client.query_opt(
+            "SELECT root FROM region_roots WHERE world = 'default' AND region_x = $1 AND region_z = $2",
+            &[&region_x, &region_z],
+        ).await.context("Failed to load region root")?;
+
+        Ok(row.map(|row| {
+            let bytes: Vec<u8> = row.get(0);
+            let mut root = [0u8; 32];
+            root.copy_from_slice(&bytes);
+            root
+        }))
+    }
+
+    // Distinct region coordinates derived from whichever coordinate table
+    // the current mode actually populates.
+    async fn list_regions(&self) -> Result<Vec<(i32, i32)>> {
+        let client = self.pool.get().await.context("Failed to get DB connection")?;
+
+        let rows = match self.mode {
+            StorageMode::PgRaw => client.query(
+                "SELECT DISTINCT (x >> 5) AS region_x, (z >> 5) AS region_z FROM chunks_raw",
+                &[],
+            ).await.context("Failed to list raw regions")?,
+            StorageMode::PgDedup => client.query(
+                "SELECT DISTINCT (chunk_x >> 5) AS region_x, (chunk_z >> 5) AS region_z FROM chunk_index",
+                &[],
+            ).await.context("Failed to list dedup regions")?,
+            _ => anyhow::bail!("list_regions not implemented for mode {:?}", self.mode),
+        };
+
+        Ok(rows.into_iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `hash_payload` is what makes `PgDedup` mode content-addressed — two
+    // identical compressed chunk blobs (e.g. the same flat-world slice
+    // reused across a whole region) must collapse to the same `blobs` row,
+    // and any difference in payload must not.
+    #[test]
+    fn test_identical_payloads_hash_identically() {
+        let a = PostgresStorage::hash_payload(b"identical chunk payload");
+        let b = PostgresStorage::hash_payload(b"identical chunk payload");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_different_payloads_hash_differently() {
+        let a = PostgresStorage::hash_payload(b"chunk payload one");
+        let b = PostgresStorage::hash_payload(b"chunk payload two");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_hash_is_32_bytes() {
+        assert_eq!(PostgresStorage::hash_payload(b"anything").len(), 32);
+    }
 }
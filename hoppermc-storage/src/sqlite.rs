@@ -0,0 +1,188 @@
+//! Embedded, single-file `ChunkStorage`, selected via `--storage
+//! sqlite:<path>` so single-machine use doesn't need a running Postgres
+//! server. WAL mode is enabled so FUSE's many concurrent reads aren't
+//! blocked behind the occasional write.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rusqlite::{params, Connection};
+
+use crate::ChunkStorage;
+
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path).context("Failed to open sqlite database")?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .context("Failed to enable WAL mode")?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS chunks (
+                region_x INTEGER NOT NULL,
+                region_z INTEGER NOT NULL,
+                chunk_x INTEGER NOT NULL,
+                chunk_z INTEGER NOT NULL,
+                data BLOB NOT NULL,
+                PRIMARY KEY (chunk_x, chunk_z)
+            );
+            CREATE TABLE IF NOT EXISTS region_roots (
+                region_x INTEGER NOT NULL,
+                region_z INTEGER NOT NULL,
+                root BLOB NOT NULL,
+                PRIMARY KEY (region_x, region_z)
+            );",
+        ).context("Failed to init sqlite schema")?;
+
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+}
+
+#[async_trait]
+impl ChunkStorage for SqliteStorage {
+    async fn save_chunk(&self, x: i32, z: i32, data: &[u8]) -> Result<()> {
+        let region_x = x.div_euclid(32);
+        let region_z = z.div_euclid(32);
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO chunks (region_x, region_z, chunk_x, chunk_z, data)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (chunk_x, chunk_z) DO UPDATE SET data = excluded.data",
+            params![region_x, region_z, x, z, data],
+        ).context("Failed to upsert chunk")?;
+        Ok(())
+    }
+
+    async fn load_chunk(&self, x: i32, z: i32) -> Result<Option<Vec<u8>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT data FROM chunks WHERE chunk_x = ?1 AND chunk_z = ?2",
+        )?;
+        let mut rows = stmt.query(params![x, z])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(row.get(0)?)),
+            None => Ok(None),
+        }
+    }
+
+    // The database's own page accounting, not a sum over the `data` column,
+    // so this also reflects index and free-page overhead. The separate
+    // `-wal` file's size is not included.
+    async fn get_total_size(&self) -> Result<u64> {
+        let conn = self.conn.lock().unwrap();
+        let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok((page_count * page_size) as u64)
+    }
+
+    async fn save_region_root(&self, region_x: i32, region_z: i32, root: [u8; 32]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let root_bytes = root.to_vec();
+        conn.execute(
+            "INSERT INTO region_roots (region_x, region_z, root) VALUES (?1, ?2, ?3)
+             ON CONFLICT (region_x, region_z) DO UPDATE SET root = excluded.root",
+            params![region_x, region_z, root_bytes],
+        ).context("Failed to upsert region root")?;
+        Ok(())
+    }
+
+    async fn load_region_root(&self, region_x: i32, region_z: i32) -> Result<Option<[u8; 32]>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT root FROM region_roots WHERE region_x = ?1 AND region_z = ?2",
+        )?;
+        let mut rows = stmt.query(params![region_x, region_z])?;
+        match rows.next()? {
+            Some(row) => {
+                let bytes: Vec<u8> = row.get(0)?;
+                let mut root = [0u8; 32];
+                root.copy_from_slice(&bytes);
+                Ok(Some(root))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn list_regions(&self) -> Result<Vec<(i32, i32)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached("SELECT DISTINCT region_x, region_z FROM chunks")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().context("Failed to list regions")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each test gets its own file under the system temp dir, named after
+    // the test so parallel `cargo test` runs don't collide.
+    fn open_fresh(name: &str) -> SqliteStorage {
+        let path = std::env::temp_dir().join(format!("hoppermc-sqlite-test-{name}.db"));
+        let _ = std::fs::remove_file(&path);
+        SqliteStorage::open(&path).expect("failed to open sqlite storage")
+    }
+
+    #[test]
+    fn test_wal_mode_is_enabled() {
+        let storage = open_fresh("wal-mode");
+        let mode: String = storage
+            .conn
+            .lock()
+            .unwrap()
+            .pragma_query_value(None, "journal_mode", |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode.to_lowercase(), "wal");
+    }
+
+    #[tokio::test]
+    async fn test_save_load_chunk_roundtrip() {
+        let storage = open_fresh("save-load-roundtrip");
+        assert_eq!(storage.load_chunk(1, 2).await.unwrap(), None);
+
+        storage.save_chunk(1, 2, b"hello chunk").await.unwrap();
+        assert_eq!(storage.load_chunk(1, 2).await.unwrap(), Some(b"hello chunk".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_save_chunk_upsert_overwrites() {
+        let storage = open_fresh("save-upsert");
+        storage.save_chunk(5, 5, b"first").await.unwrap();
+        storage.save_chunk(5, 5, b"second").await.unwrap();
+        assert_eq!(storage.load_chunk(5, 5).await.unwrap(), Some(b"second".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_region_root_roundtrip() {
+        let storage = open_fresh("region-root-roundtrip");
+        assert_eq!(storage.load_region_root(0, 0).await.unwrap(), None);
+
+        let root = [7u8; 32];
+        storage.save_region_root(3, -4, root).await.unwrap();
+        assert_eq!(storage.load_region_root(3, -4).await.unwrap(), Some(root));
+    }
+
+    #[tokio::test]
+    async fn test_list_regions_reflects_saved_chunks() {
+        let storage = open_fresh("list-regions");
+        storage.save_chunk(0, 0, b"a").await.unwrap();
+        storage.save_chunk(40, 40, b"b").await.unwrap(); // region (1, 1)
+
+        let mut regions = storage.list_regions().await.unwrap();
+        regions.sort();
+        assert_eq!(regions, vec![(0, 0), (1, 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_get_total_size_grows_after_save() {
+        let storage = open_fresh("total-size");
+        let before = storage.get_total_size().await.unwrap();
+        storage.save_chunk(9, 9, &vec![0u8; 8192]).await.unwrap();
+        let after = storage.get_total_size().await.unwrap();
+        assert!(after > before, "expected total size to grow: before={before} after={after}");
+    }
+}
@@ -6,6 +6,7 @@ use hoppermc_gen::flat::FlatGenerator;
 use hoppermc_gen::vanilla::VanillaWorldGenerator;
 use hoppermc_gen::WorldGenerator;
 use hoppermc_fs::virtual_file::VirtualFile;
+use pumpkin_world::dimension::Dimension;
 
 #[derive(Parser)]
 #[command(name = "hoppermc", about = "FUSE-based virtual filesystem for Minecraft with Storage Backends")]
@@ -21,7 +22,7 @@ pub struct Args {
     #[arg(long, env = "SEED", default_value = "0")]
     pub seed: u64,
     
-    /// Storage mode: "nostorage", "pg_raw", or "pg_jsonb"
+    /// Storage mode: "nostorage", "pg_raw", "pg_jsonb", "pg_dedup", or "sqlite:<path>"
     #[arg(long, env = "STORAGE", default_value = "pg_raw")]
     pub storage: String,
 
@@ -40,6 +41,29 @@ pub struct Args {
     /// Duration for each benchmark cycle (seconds)
     #[arg(long, env("BENCHMARK_CYCLE_DURATION"), default_value_t = 60)]
     pub benchmark_cycle_duration: u64,
+
+    /// Passphrase to derive an at-rest encryption key from. When set, chunk
+    /// blobs are transparently encrypted before reaching storage.
+    #[arg(long, env = "HOPPERMC_ENCRYPTION_KEY")]
+    pub encryption_key: Option<String>,
+
+    /// Recompute and verify every stored region's Merkle root instead of
+    /// mounting.
+    #[arg(long, default_value_t = false)]
+    pub scrub: bool,
+
+    /// Peer/replica base URL to eventually fetch only the differing leaves
+    /// from when a region's root doesn't match.
+    #[arg(long)]
+    pub scrub_peer: Option<String>,
+
+    /// Comma-separated dimensions to generate for: "overworld", "nether",
+    /// "end". Only the vanilla generator tells these apart; flat always
+    /// produces the same overworld-style terrain regardless of dimension.
+    /// Only one dimension is mounted per run: naming more than one exits
+    /// with an error before mounting.
+    #[arg(long, env = "DIMENSIONS", default_value = "overworld", value_delimiter = ',')]
+    pub dimensions: Vec<String>,
 }
 
 #[tokio::main]
@@ -47,62 +71,104 @@ async fn main() {
     env_logger::init();
     let args = Args::parse();
     
-    use hoppermc_storage::{postgres::PostgresStorage, StorageMode, ChunkStorage};
+    use hoppermc_storage::{postgres::PostgresStorage, sqlite::SqliteStorage, StorageMode, ChunkStorage};
     use std::sync::Arc;
-    
-    // Initialize storage based on mode
-    let storage: Option<Arc<dyn ChunkStorage>> = match args.storage.to_lowercase().as_str() {
-        "nostorage" | "none" | "stateless" => {
-            println!("Storage mode: NOSTORAGE (stateless, all chunks generated on-the-fly)");
-            None
-        },
-        "pg_raw" | "raw" | "postgres" | "pg_jsonb" | _ => {
-            let database_url = std::env::var("DATABASE_URL")
-                .unwrap_or_else(|_| "postgres://postgres:postgres@db:5432/hoppermc".to_string());
-            
-            let mode = if args.storage.to_lowercase() == "pg_jsonb" {
-                StorageMode::PgJsonb
-            } else {
-                StorageMode::PgRaw
-            };
 
-            println!("Storage mode: {:?} (PostgreSQL)", mode);
-            println!("Connecting to storage at {}...", database_url);
-            
-            // Retry loop for DB connection
-            let mut storage_backend = None;
-            for i in 0..30 {
-                match PostgresStorage::new(&database_url, mode).await {
-                    Ok(s) => {
-                        storage_backend = Some(s);
-                        break;
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to connect to storage: {}. Retrying {}/30 in 2s...", e, i + 1);
-                        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    // One generator per requested dimension, built up front so storage
+    // init below knows which dimension's namespace (see
+    // `hoppermc_storage::dimensioned::DimensionedStorage`) to wrap the
+    // backend in. The vanilla generator is the only one that actually
+    // varies its output per dimension; flat mode just reuses the same
+    // generator under every dimension's directory.
+    let dimension_generators = dimension_generators(&args);
+    let (_mount_dir_name, mount_dimension, generator) = dimension_generators
+        .first()
+        .cloned()
+        .expect("--dimensions must name at least one dimension");
+    let mount_dimension_id = hoppermc_gen::dimension_id(&mount_dimension);
+
+    // Initialize storage based on mode
+    let storage: Option<Arc<dyn ChunkStorage>> = if let Some(path) = args.storage.strip_prefix("sqlite:") {
+        println!("Storage mode: SQLITE (embedded, {})", path);
+        let backend = SqliteStorage::open(path).expect("FATAL: Failed to open sqlite storage");
+        let backend = hoppermc_storage::dimensioned::DimensionedStorage::new(backend, mount_dimension_id);
+        Some(wrap_with_encryption(backend, args.encryption_key.as_deref()).await)
+    } else {
+        match args.storage.to_lowercase().as_str() {
+            "nostorage" | "none" | "stateless" => {
+                println!("Storage mode: NOSTORAGE (stateless, all chunks generated on-the-fly)");
+                None
+            },
+            "pg_raw" | "raw" | "postgres" | "pg_jsonb" | "pg_dedup" | _ => {
+                let database_url = std::env::var("DATABASE_URL")
+                    .unwrap_or_else(|_| "postgres://postgres:postgres@db:5432/hoppermc".to_string());
+
+                let mode = match args.storage.to_lowercase().as_str() {
+                    "pg_jsonb" => StorageMode::PgJsonb,
+                    "pg_dedup" => StorageMode::PgDedup,
+                    _ => StorageMode::PgRaw,
+                };
+
+                println!("Storage mode: {:?} (PostgreSQL)", mode);
+                println!("Connecting to storage at {}...", database_url);
+
+                // Retry loop for DB connection
+                let mut storage_backend = None;
+                for i in 0..30 {
+                    match PostgresStorage::new(&database_url, mode).await {
+                        Ok(s) => {
+                            storage_backend = Some(s);
+                            break;
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to connect to storage: {}. Retrying {}/30 in 2s...", e, i + 1);
+                            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                        }
                     }
                 }
-            }
 
-            let backend = storage_backend.expect("FATAL: Could not connect to storage after 30 retries.");
-            Some(Arc::new(backend) as Arc<dyn ChunkStorage>)
+                let backend = storage_backend.expect("FATAL: Could not connect to storage after 30 retries.");
+                let backend = hoppermc_storage::dimensioned::DimensionedStorage::new(backend, mount_dimension_id);
+                Some(wrap_with_encryption(backend, args.encryption_key.as_deref()).await)
+            }
         }
     };
 
+    if args.scrub {
+        run_scrub(storage, args.scrub_peer).await;
+        return;
+    }
+
     use fuser::MountOption;
     let options = vec![MountOption::AllowOther, MountOption::RW];
 
-    // Select generator based on CLI args
-    let generator: Arc<dyn WorldGenerator> = match args.generator.as_str() {
-        "vanilla" => {
-            println!("Using Pumpkin VanillaGenerator with seed: {}", args.seed);
-            Arc::new(VanillaWorldGenerator::new(args.seed))
-        },
-        "flat" | _ => {
-            println!("Using FlatGenerator");
-            Arc::new(FlatGenerator)
-        },
-    };
+    if dimension_generators.len() > 1 {
+        // Each dimension's chunk storage namespace is ready — the
+        // `DimensionedStorage` wrap above means sibling dimensions pointed
+        // at the same backend won't collide. What's still missing is
+        // mounting more than one dimension's region tree as its own
+        // top-level FUSE directory (`overworld/region`, `DIM-1/region`,
+        // ...), which needs `hoppermc_fs::McFUSE` to route
+        // `lookup`/`readdir` through `pack_generic`-encoded dimension
+        // inodes — that doesn't exist in this crate yet. Silently mounting
+        // only the first dimension while the storage layer already
+        // namespaces all of them would leave a server owner looking at a
+        // half-populated world save with no indication anything's missing,
+        // so refuse to start rather than serve a silently incomplete mount.
+        eprintln!(
+            "FATAL: --dimensions named {} dimensions ({}), but multi-dimension \
+             FUSE directories aren't wired up yet — only a single dimension can \
+             be mounted per run. Pass a single --dimensions value (e.g. \
+             --dimensions overworld) per mountpoint until this lands.",
+            dimension_generators.len(),
+            dimension_generators
+                .iter()
+                .map(|(name, _, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        std::process::exit(1);
+    }
 
     // Initialize Benchmark with Config Summary
     use hoppermc_benchmark::BenchmarkMetrics;
@@ -112,7 +178,10 @@ async fn main() {
             "Gen: {} | Seed: {} | Storage: {} | Cache: {} | Prefetch: {}", 
             args.generator, args.seed, args.storage, args.cache_size, args.prefetch_radius
         );
-        Some(Arc::new(BenchmarkMetrics::new(config_summary)))
+        let dimension_labels = dimension_generators.iter().map(|(name, _, _)| name.to_string()).collect();
+        let metrics = Arc::new(BenchmarkMetrics::new(config_summary, dimension_labels));
+        hoppermc_benchmark::SystemSampler::spawn(metrics.clone(), hoppermc_benchmark::DEFAULT_SAMPLE_INTERVAL);
+        Some(metrics)
     } else {
         None
     };
@@ -159,6 +228,122 @@ async fn main() {
     }
 }
 
+/// Builds one generator per `--dimensions` entry, labeled with its save
+/// directory name (see [`hoppermc_gen::dimension_dir_name`]) and its parsed
+/// `Dimension` (so callers can also derive a storage namespace via
+/// [`hoppermc_gen::dimension_id`]). Panics on an unrecognized dimension
+/// name, unlike `--generator`/`--storage`, whose unrecognized values fall
+/// back to the flat generator and `pg_raw`.
+fn dimension_generators(args: &Args) -> Vec<(&'static str, Dimension, Arc<dyn WorldGenerator>)> {
+    args.dimensions
+        .iter()
+        .map(|name| {
+            let dimension = hoppermc_gen::parse_dimension(name)
+                .unwrap_or_else(|e| panic!("invalid --dimensions entry: {e}"));
+            let dir_name = hoppermc_gen::dimension_dir_name(&dimension);
+
+            let generator: Arc<dyn WorldGenerator> = match args.generator.as_str() {
+                "vanilla" => {
+                    println!("Using Pumpkin VanillaGenerator with seed: {} for {}", args.seed, dir_name);
+                    Arc::new(VanillaWorldGenerator::with_dimension(args.seed, dimension.clone()))
+                }
+                "flat" | _ => {
+                    println!("Using FlatGenerator for {}", dir_name);
+                    Arc::new(FlatGenerator)
+                }
+            };
+
+            (dir_name, dimension, generator)
+        })
+        .collect()
+}
+
+/// Wraps `backend` in [`hoppermc_storage::encrypted::EncryptedStorage`]
+/// when a passphrase is configured, otherwise returns it as-is — shared by
+/// every storage backend's setup path so enabling encryption doesn't
+/// depend on which one was picked.
+async fn wrap_with_encryption<S: hoppermc_storage::ChunkStorage + Send + Sync + 'static>(
+    backend: S,
+    passphrase: Option<&str>,
+) -> std::sync::Arc<dyn hoppermc_storage::ChunkStorage> {
+    match passphrase {
+        Some(passphrase) => {
+            println!("Encryption: enabled (chunk blobs encrypted at rest)");
+            let encrypted = hoppermc_storage::encrypted::EncryptedStorage::new(backend, passphrase)
+                .await
+                .expect("FATAL: Failed to initialize encrypted storage");
+            std::sync::Arc::new(encrypted) as std::sync::Arc<dyn hoppermc_storage::ChunkStorage>
+        }
+        None => std::sync::Arc::new(backend) as std::sync::Arc<dyn hoppermc_storage::ChunkStorage>,
+    }
+}
+
+/// Walks every region the configured storage backend has chunks for,
+/// recomputes its Merkle root over the live data, and compares it against
+/// the root last persisted for that region — reporting mismatches instead
+/// of silently trusting whatever's on disk.
+async fn run_scrub(storage: Option<std::sync::Arc<dyn hoppermc_storage::ChunkStorage>>, peer: Option<String>) {
+    use hoppermc_storage::merkle;
+
+    let Some(storage) = storage else {
+        println!("Scrub: no storage backend configured (--storage nostorage), nothing to verify.");
+        return;
+    };
+
+    let regions = match storage.list_regions().await {
+        Ok(regions) => regions,
+        Err(e) => {
+            eprintln!("Scrub: failed to list stored regions: {}", e);
+            return;
+        }
+    };
+    let region_count = regions.len();
+
+    println!("Scrub: checking {} region(s)...", region_count);
+    let mut mismatches = 0;
+
+    for (region_x, region_z) in regions {
+        let fresh_root = match merkle::compute_region_root(storage.as_ref(), region_x, region_z).await {
+            Ok(root) => root,
+            Err(e) => {
+                eprintln!("Scrub: failed to recompute root for region ({}, {}): {}", region_x, region_z, e);
+                continue;
+            }
+        };
+
+        match storage.load_region_root(region_x, region_z).await {
+            Ok(Some(stored_root)) if stored_root == fresh_root => {}
+            Ok(Some(_)) => {
+                mismatches += 1;
+                println!("Scrub: MISMATCH in region ({}, {})", region_x, region_z);
+                if let Some(peer_url) = &peer {
+                    // Recomputing which leaves actually diverge only helps
+                    // once there's a peer-side endpoint to fetch them from;
+                    // this build doesn't have one yet.
+                    println!(
+                        "Scrub: would fetch only the differing leaves from {} for region ({}, {}), \
+                         but peer scrub transport isn't implemented yet.",
+                        peer_url, region_x, region_z
+                    );
+                }
+            }
+            Ok(None) => {
+                println!("Scrub: no baseline root for region ({}, {}), recording the current one.", region_x, region_z);
+            }
+            Err(e) => {
+                eprintln!("Scrub: failed to load stored root for region ({}, {}): {}", region_x, region_z, e);
+                continue;
+            }
+        }
+
+        if let Err(e) = storage.save_region_root(region_x, region_z, fresh_root).await {
+            eprintln!("Scrub: failed to persist root for region ({}, {}): {}", region_x, region_z, e);
+        }
+    }
+
+    println!("Scrub complete: {} mismatch(es) out of {} region(s).", mismatches, region_count);
+}
+
 fn write_report(report: String) {
     let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
     if let Err(e) = std::fs::create_dir_all("benchmarks") {
@@ -173,6 +358,15 @@ fn write_report(report: String) {
     }
 }
 
+/// Which backend [`run_auto_benchmark`] should stand up for one config row
+/// — plain `Option<StorageMode>` stopped being enough once the embedded
+/// sqlite backend (a different struct entirely) joined the comparison.
+enum BenchStorageKind {
+    None,
+    Postgres(hoppermc_storage::StorageMode),
+    Sqlite(std::path::PathBuf),
+}
+
 async fn run_auto_benchmark(args: Args, _main_bench: Option<std::sync::Arc<hoppermc_benchmark::BenchmarkMetrics>>) {
     use hoppermc_storage::{postgres::PostgresStorage, StorageMode, ChunkStorage};
     use hoppermc_gen::flat::FlatGenerator;
@@ -195,33 +389,44 @@ async fn run_auto_benchmark(args: Args, _main_bench: Option<std::sync::Arc<hoppe
     let database_url = std::env::var("DATABASE_URL")
         .unwrap_or_else(|_| "postgres://postgres:postgres@db:5432/hoppermc".to_string());
 
-    let storage_configs: Vec<(&str, Option<StorageMode>)> = vec![
-        ("nostorage", None),
-        ("pg_raw", Some(StorageMode::PgRaw)),
-        ("pg_jsonb", Some(StorageMode::PgJsonb)),
+    let storage_configs: Vec<(&str, BenchStorageKind)> = vec![
+        ("nostorage", BenchStorageKind::None),
+        ("pg_raw", BenchStorageKind::Postgres(StorageMode::PgRaw)),
+        ("pg_jsonb", BenchStorageKind::Postgres(StorageMode::PgJsonb)),
+        ("pg_dedup", BenchStorageKind::Postgres(StorageMode::PgDedup)),
+        ("sqlite", BenchStorageKind::Sqlite(std::env::temp_dir().join("hoppermc-bench.db"))),
     ];
 
     let mut full_report = String::new();
     full_report.push_str("# HopperMC Auto-Benchmark Suite\n\n");
 
     for (gen_name, gen_arc) in generators {
-        for (storage_name, storage_mode) in &storage_configs {
+        for (storage_name, storage_kind) in &storage_configs {
             println!("\n>>> Testing: Gen={} | Storage={}", gen_name, storage_name);
-            
-            let storage: Option<Arc<dyn ChunkStorage>> = if let Some(mode) = storage_mode {
-                match PostgresStorage::new(&database_url, *mode).await {
+
+            let storage: Option<Arc<dyn ChunkStorage>> = match storage_kind {
+                BenchStorageKind::None => None,
+                BenchStorageKind::Postgres(mode) => match PostgresStorage::new(&database_url, *mode).await {
                     Ok(s) => Some(Arc::new(s) as Arc<dyn ChunkStorage>),
                     Err(e) => {
                         eprintln!("Skipping {} due to connection error: {}", storage_name, e);
                         continue;
                     }
+                },
+                BenchStorageKind::Sqlite(path) => {
+                    let _ = std::fs::remove_file(path);
+                    match hoppermc_storage::sqlite::SqliteStorage::open(path) {
+                        Ok(s) => Some(Arc::new(s) as Arc<dyn ChunkStorage>),
+                        Err(e) => {
+                            eprintln!("Skipping {} due to open error: {}", storage_name, e);
+                            continue;
+                        }
+                    }
                 }
-            } else {
-                None
             };
 
             let config_summary = format!("Gen: {} | Storage: {}", gen_name, storage_name);
-            let bench = Arc::new(BenchmarkMetrics::new(config_summary));
+            let bench = Arc::new(BenchmarkMetrics::new(config_summary, vec![]));
             let handle = tokio::runtime::Handle::current();
             let vf = Arc::new(VirtualFile::new(gen_arc.clone(), storage.clone(), handle, Some(bench.clone()), args.cache_size, args.prefetch_radius));
 
@@ -0,0 +1,86 @@
+//! Numeric block-state ID registry.
+//!
+//! Maps between a [`BlockState`] (name + property set) and the compact
+//! `u16` id that the network protocol and block-state palettes ultimately
+//! need. Backed by a generated table, indexed directly by raw id; extend
+//! [`TABLE`] as more blocks need numeric ids.
+
+use crate::nbt::BlockState;
+
+struct Entry {
+    name: &'static str,
+    properties: &'static [(&'static str, &'static str)],
+}
+
+// Row `i` maps to raw id `i`. Keep new entries appended at the end so
+// existing ids never shift.
+const TABLE: &[Entry] = &[
+    Entry { name: "minecraft:air", properties: &[] },
+    Entry { name: "minecraft:stone", properties: &[] },
+    Entry { name: "minecraft:dirt", properties: &[] },
+    Entry { name: "minecraft:grass_block", properties: &[("snowy", "false")] },
+    Entry { name: "minecraft:grass_block", properties: &[("snowy", "true")] },
+    Entry { name: "minecraft:oak_log", properties: &[("axis", "x")] },
+    Entry { name: "minecraft:oak_log", properties: &[("axis", "y")] },
+    Entry { name: "minecraft:oak_log", properties: &[("axis", "z")] },
+    Entry { name: "minecraft:oak_slab", properties: &[("type", "bottom"), ("waterlogged", "false")] },
+    Entry { name: "minecraft:oak_slab", properties: &[("type", "top"), ("waterlogged", "false")] },
+    Entry { name: "minecraft:oak_slab", properties: &[("type", "double"), ("waterlogged", "false")] },
+    Entry { name: "minecraft:oak_fence", properties: &[("waterlogged", "false")] },
+    Entry { name: "minecraft:oak_fence", properties: &[("waterlogged", "true")] },
+];
+
+fn entry_to_state(entry: &Entry) -> BlockState {
+    BlockState {
+        name: entry.name.to_string(),
+        properties: if entry.properties.is_empty() {
+            None
+        } else {
+            Some(
+                entry
+                    .properties
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            )
+        },
+    }
+}
+
+fn properties_match(state: &BlockState, entry_properties: &[(&str, &str)]) -> bool {
+    match state.properties.as_ref() {
+        Some(props) => {
+            props.len() == entry_properties.len()
+                && entry_properties
+                    .iter()
+                    .all(|(k, v)| props.get(*k).map(|sv| sv == v).unwrap_or(false))
+        }
+        None => entry_properties.is_empty(),
+    }
+}
+
+/// Largest raw id currently registered.
+pub fn max_raw() -> u16 {
+    (TABLE.len() - 1) as u16
+}
+
+/// Looks up the block state for a raw id, if registered.
+pub fn from_raw(id: u16) -> Option<BlockState> {
+    TABLE.get(id as usize).map(entry_to_state)
+}
+
+/// Same as [`from_raw`], but returns the state directly instead of an
+/// `Option` — only call this on hot paths that already know `id` is valid
+/// (e.g. re-reading a palette this registry itself produced). Panics if
+/// `id` isn't registered.
+pub fn from_raw_unchecked(id: u16) -> BlockState {
+    entry_to_state(&TABLE[id as usize])
+}
+
+/// Looks up the raw id for a block state (name + exact property set).
+pub fn to_raw(state: &BlockState) -> Option<u16> {
+    TABLE
+        .iter()
+        .position(|entry| entry.name == state.name && properties_match(state, entry.properties))
+        .map(|index| index as u16)
+}
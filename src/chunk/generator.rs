@@ -6,7 +6,7 @@ use std::io::Write;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
 
-use crate::nbt::{ChunkData, Section, BlockStates, Biomes, BlockState, DATA_VERSION};
+use crate::nbt::{ChunkData, Section, BlockStates, Biomes, BlockState, get_data_version, pack_light, LIGHT_LEVEL_COUNT};
 
 /// Procedural chunk generator.
 ///
@@ -37,26 +37,37 @@ impl Generator {
 
             let palette = vec![BlockState {
                 name: block_name.to_string(),
+                properties: None,
             }];
 
+            // Every section above the dirt layer is open air with nothing
+            // overhead, so it gets full sky light; the dirt section itself
+            // is opaque and dark. The flat world has no light sources.
+            let is_air_section = section_y != -4;
+            let sky_light = pack_light(&[if is_air_section { 15u8 } else { 0u8 }; LIGHT_LEVEL_COUNT]);
+            let block_light = pack_light(&[0u8; LIGHT_LEVEL_COUNT]);
+
             sections.push(Section {
                 y: section_y,
-                block_states: BlockStates { palette },
-                biomes: Biomes {
+                block_states: Some(BlockStates { palette, data: None }),
+                biomes: Some(Biomes {
                     palette: vec!["minecraft:plains".to_string()],
-                },
+                    data: None,
+                }),
+                block_light: Some(block_light),
+                sky_light: Some(sky_light),
             });
         }
 
         let chunk = ChunkData {
-            data_version: DATA_VERSION,
+            data_version: get_data_version(),
             x_pos: chunk_x,
             z_pos: chunk_z,
             y_pos: -4,
             status: "minecraft:full".to_string(),
             last_update: 0,
             inhabited_time: 0,
-            is_light_on: 1,
+            is_light_on: Some(1),
             sections,
         };
 
@@ -10,7 +10,17 @@ mod generator;
 pub use generator::Generator;
 
 use std::sync::Arc;
-use crate::storage::{ChunkPos, ChunkStorage};
+
+use anyhow::Result;
+
+use crate::storage::ChunkStorage;
+
+/// A chunk's absolute coordinates (in chunks, not blocks).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkPos {
+    pub x: i32,
+    pub z: i32,
+}
 
 /// Provides chunks by checking storage first, then falling back to generation.
 ///
@@ -32,23 +42,27 @@ impl<S: ChunkStorage> ChunkProvider<S> {
 
     /// Get chunk data (from storage or generate new).
     /// Returns raw MCA-formatted bytes (length + compression type + compressed NBT).
-    pub fn get_chunk(&self, pos: ChunkPos) -> Vec<u8> {
+    pub fn get_chunk(&self, pos: ChunkPos) -> Result<Vec<u8>> {
         // First check storage for modified chunks
-        if let Some(data) = self.storage.get(pos) {
-            return data;
+        if let Some(data) = self.storage.read_chunk(pos.x, pos.z)? {
+            return Ok(data);
         }
 
         // Generate new chunk
-        self.generator.generate(pos.x, pos.z)
+        Ok(self.generator.generate(pos.x, pos.z))
     }
 
     /// Save chunk data to storage.
-    pub fn save_chunk(&self, pos: ChunkPos, data: Vec<u8>) {
-        self.storage.set(pos, data);
+    pub fn save_chunk(&self, pos: ChunkPos, data: Vec<u8>) -> Result<()> {
+        self.storage.write_chunk(pos.x, pos.z, &data)
     }
 
     /// Check if chunk has been modified (exists in storage).
     pub fn is_modified(&self, pos: ChunkPos) -> bool {
-        self.storage.exists(pos)
+        self.storage
+            .read_chunk(pos.x, pos.z)
+            .ok()
+            .flatten()
+            .is_some()
     }
 }
@@ -0,0 +1,122 @@
+//! Bounded LRU cache for finished, compressed chunk blobs.
+//!
+//! `read` is called by the kernel in many small, overlapping byte ranges
+//! over the same chunk. Without this, every one of those calls re-runs
+//! world generation plus a fresh compressor for the exact same bytes. This
+//! is the same fix qcow-style virtual disks use for their clustered page
+//! cache: hold the finished blob and evict by a total byte budget (LRU)
+//! rather than by entry count, since chunk blobs vary widely in size.
+
+use std::sync::Arc;
+
+use quick_cache::sync::Cache;
+use quick_cache::Weighter;
+
+/// `(region_x, region_z, rel_x, rel_z)` — identifies one chunk's finished
+/// blob unambiguously, including which region's coordinate space it was
+/// built under (different regions never reuse a key by construction). The
+/// codec is not part of the key.
+pub type ChunkKey = (i32, i32, i32, i32);
+
+#[derive(Clone)]
+struct BlobWeighter;
+
+impl Weighter<ChunkKey, Arc<Vec<u8>>> for BlobWeighter {
+    fn weight(&self, _key: &ChunkKey, blob: &Arc<Vec<u8>>) -> u64 {
+        blob.len() as u64
+    }
+}
+
+/// Memory budget used by [`McFUSE::new`](super::McFUSE::new).
+pub const DEFAULT_CACHE_BUDGET_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Thread-safe, byte-budgeted LRU cache of finished `[length][type][data]`
+/// chunk blobs.
+pub struct ChunkCache {
+    cache: Cache<ChunkKey, Arc<Vec<u8>>, BlobWeighter>,
+}
+
+impl ChunkCache {
+    /// `budget_bytes` bounds total cached blob size, not entry count — a
+    /// region with many large (e.g. uncompressed) chunks simply evicts
+    /// sooner.
+    pub fn new(budget_bytes: u64) -> Self {
+        // Estimated item count only sizes the cache's internal hash table;
+        // a rough "average 64KB blob" guess keeps that table reasonably
+        // pre-sized without needing an exact count up front.
+        let estimated_items = ((budget_bytes / (64 * 1024)).max(1)) as usize;
+        Self {
+            cache: Cache::with(
+                estimated_items,
+                budget_bytes,
+                BlobWeighter,
+                quick_cache::sync::DefaultHashBuilder::default(),
+            ),
+        }
+    }
+
+    pub fn get(&self, key: &ChunkKey) -> Option<Arc<Vec<u8>>> {
+        self.cache.get(key)
+    }
+
+    pub fn insert(&self, key: ChunkKey, blob: Arc<Vec<u8>>) {
+        self.cache.insert(key, blob);
+    }
+
+    /// Evicts a chunk whose cached blob is now stale (e.g. a fresh write
+    /// just landed in the overlay behind it).
+    pub fn remove(&self, key: &ChunkKey) {
+        self.cache.remove(key);
+    }
+}
+
+impl Default for ChunkCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_BUDGET_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_then_get_returns_the_same_blob() {
+        let cache = ChunkCache::new(1024 * 1024);
+        let key: ChunkKey = (0, 0, 1, 2);
+        let blob = Arc::new(vec![1u8, 2, 3, 4]);
+        cache.insert(key, blob.clone());
+        assert_eq!(cache.get(&key), Some(blob));
+    }
+
+    #[test]
+    fn test_get_on_an_unknown_key_is_none() {
+        let cache = ChunkCache::new(1024 * 1024);
+        assert_eq!(cache.get(&(0, 0, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_remove_evicts_the_entry() {
+        let cache = ChunkCache::new(1024 * 1024);
+        let key: ChunkKey = (1, 1, 0, 0);
+        cache.insert(key, Arc::new(vec![0u8; 16]));
+        cache.remove(&key);
+        assert_eq!(cache.get(&key), None);
+    }
+
+    #[test]
+    fn test_budget_evicts_once_blobs_exceed_it() {
+        // Tight enough budget that a third 64-byte blob forces an eviction.
+        let cache = ChunkCache::new(128);
+        let a: ChunkKey = (0, 0, 0, 0);
+        let b: ChunkKey = (0, 0, 0, 1);
+        let c: ChunkKey = (0, 0, 0, 2);
+
+        cache.insert(a, Arc::new(vec![0u8; 64]));
+        cache.insert(b, Arc::new(vec![0u8; 64]));
+        cache.insert(c, Arc::new(vec![0u8; 64]));
+
+        let still_present = [a, b, c].into_iter().filter(|k| cache.get(k).is_some()).count();
+        assert!(still_present <= 2, "budget of 128 bytes should not fit three 64-byte blobs");
+    }
+}
@@ -1,24 +1,262 @@
 use fuser::{FileAttr, FileType, Filesystem, Request};
 use libc::ENOENT;
+use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use std::io::Write;
-use crate::region;
+use crate::region::{self, CompressionType, RegionLayout};
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
 use crate::generator::WorldGenerator;
+use crate::storage::ChunkStorage;
 
-// Minecraft Understands only zlib (gzip, nocomp, custom) compression
-// but it is much easier to use just zlib (no futher configuration we need)
-use flate2::write::ZlibEncoder;
-use flate2::Compression;
+mod chunk_cache;
+use chunk_cache::{ChunkCache, ChunkKey};
 
 pub struct McFUSE {
     pub generator: Arc<dyn WorldGenerator>,
+    /// How many regions out from the origin `readdir` enumerates in each
+    /// direction (a `(2*radius+1)^2` window). Every `r.X.Z.mca` inside the
+    /// world is still reachable directly via `lookup`, regardless of
+    /// whether it falls inside this window.
+    pub region_radius: i32,
+    /// Codec used to compress every chunk, once when its region's layout is
+    /// built and again whenever `read` misses `chunk_cache`. LZ4 is far
+    /// cheaper per chunk than Zlib/Gzip at the cost of a larger on-disk
+    /// blob — pick it for CPU-bound mounts, Zlib/Gzip for bandwidth-bound ones.
+    pub compression: CompressionType,
+    /// Per-region sector layout, built once (generating and compressing
+    /// every chunk in that region) and reused for every subsequent
+    /// `getattr`/`read` — generation is deterministic, so there's no need
+    /// to repack a region more than once.
+    region_layouts: RwLock<HashMap<(i32, i32), Arc<RegionLayout>>>,
+    /// Finished chunk blobs, keyed by `(region_x, region_z, rel_x, rel_z)`.
+    /// `read` is called in many small overlapping ranges over the same
+    /// chunk, so this turns repeated reads into a memcpy instead of
+    /// regenerating and recompressing every time.
+    chunk_cache: ChunkCache,
+    /// Write-through overlay: chunks saved here take priority over
+    /// procedural generation, letting edits persist across reads. `None`
+    /// means every chunk stays purely procedural (the old behavior).
+    overlay: Option<Arc<dyn ChunkStorage + Send + Sync>>,
+    /// Partial `write` data for a chunk whose `[Length:4][Type:1][Data]`
+    /// frame hasn't fully arrived yet, keyed the same as `chunk_cache`.
+    write_buffers: Mutex<HashMap<ChunkKey, Vec<u8>>>,
+    /// Per-region last-write-time table (Unix seconds), surfaced as the
+    /// real header's timestamp table. Missing entries read as `0`
+    /// (never written), matching real Anvil.
+    write_timestamps: RwLock<HashMap<(i32, i32), Vec<u32>>>,
 }
 
+/// `readdir`'s window size when constructed via [`McFUSE::new`].
+const DEFAULT_REGION_RADIUS: i32 = 4;
 
+impl McFUSE {
+    pub fn new(generator: Arc<dyn WorldGenerator>) -> Self {
+        Self {
+            generator,
+            region_radius: DEFAULT_REGION_RADIUS,
+            compression: CompressionType::default(),
+            region_layouts: RwLock::new(HashMap::new()),
+            chunk_cache: ChunkCache::default(),
+            overlay: None,
+            write_buffers: Mutex::new(HashMap::new()),
+            write_timestamps: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_region_radius(generator: Arc<dyn WorldGenerator>, region_radius: i32) -> Self {
+        Self {
+            region_radius,
+            ..Self::new(generator)
+        }
+    }
+
+    /// Same as [`Self::new`], but with an explicit chunk compression codec.
+    pub fn with_compression(generator: Arc<dyn WorldGenerator>, compression: CompressionType) -> Self {
+        Self {
+            compression,
+            ..Self::new(generator)
+        }
+    }
+
+    /// Same as [`Self::new`], but with an explicit chunk blob cache budget
+    /// in bytes (see [`ChunkCache`]).
+    pub fn with_cache_budget(generator: Arc<dyn WorldGenerator>, cache_budget_bytes: u64) -> Self {
+        Self {
+            chunk_cache: ChunkCache::new(cache_budget_bytes),
+            ..Self::new(generator)
+        }
+    }
+
+    /// Same as [`Self::new`], but backed by a write-through overlay: chunks
+    /// saved to `overlay` (e.g. a [`crate::storage::RegionFileStorage`])
+    /// are served in place of procedural generation, and `write` persists
+    /// edits into it instead of discarding them.
+    pub fn with_overlay(
+        generator: Arc<dyn WorldGenerator>,
+        overlay: Arc<dyn ChunkStorage + Send + Sync>,
+    ) -> Self {
+        Self {
+            overlay: Some(overlay),
+            ..Self::new(generator)
+        }
+    }
+
+    /// Returns the cached [`RegionLayout`] for `(region_x, region_z)`,
+    /// building and caching it first if this is the first time it's seen.
+    fn layout_for(&self, region_x: i32, region_z: i32) -> Arc<RegionLayout> {
+        if let Some(layout) = self.region_layouts.read().unwrap().get(&(region_x, region_z)) {
+            return layout.clone();
+        }
+
+        let layout = Arc::new(RegionLayout::build(
+            self.generator.as_ref(),
+            self.overlay.as_deref().map(|o| o as &dyn ChunkStorage),
+            region_x,
+            region_z,
+            self.compression,
+        ));
+        self.region_layouts
+            .write()
+            .unwrap()
+            .entry((region_x, region_z))
+            .or_insert(layout)
+            .clone()
+    }
+
+    /// Returns the 1024-entry last-write-time table for `(region_x,
+    /// region_z)`, all zeros if nothing in it has ever been written.
+    fn timestamps_for(&self, region_x: i32, region_z: i32) -> Vec<u32> {
+        self.write_timestamps
+            .read()
+            .unwrap()
+            .get(&(region_x, region_z))
+            .cloned()
+            .unwrap_or_else(|| vec![0u32; 1024])
+    }
+
+    /// Records that chunk `chunk_index` in `(region_x, region_z)` was just
+    /// written, and drops that region's cached layout so the next access
+    /// rebuilds it — consulting the overlay this time, now that it has a
+    /// fresher copy of this chunk.
+    fn record_write(&self, region_x: i32, region_z: i32, chunk_index: usize, when: u32) {
+        let mut timestamps = self.write_timestamps.write().unwrap();
+        let table = timestamps.entry((region_x, region_z)).or_insert_with(|| vec![0u32; 1024]);
+        table[chunk_index] = when;
+        drop(timestamps);
+
+        self.region_layouts.write().unwrap().remove(&(region_x, region_z));
+    }
+
+    /// Buffers one `write` call's bytes at their place in chunk
+    /// `chunk_index`'s `[Length:4][Type:1][Data]` frame. Once the full
+    /// frame has arrived, decompresses it and persists the raw NBT into
+    /// the overlay (a no-op if there's no overlay configured).
+    fn assemble_and_persist_write(
+        &self,
+        region_x: i32,
+        region_z: i32,
+        chunk_index: usize,
+        local_offset: u64,
+        data: &[u8],
+    ) {
+        let Some(overlay) = self.overlay.as_ref() else {
+            return;
+        };
+
+        let (rel_x, rel_z) = region::rel_coords(chunk_index);
+        let key: ChunkKey = (region_x, region_z, rel_x, rel_z);
+
+        let frame = {
+            let mut buffers = self.write_buffers.lock().unwrap();
+            let buffer = buffers.entry(key).or_default();
+
+            let end = local_offset as usize + data.len();
+            if buffer.len() < end {
+                buffer.resize(end, 0);
+            }
+            buffer[local_offset as usize..end].copy_from_slice(data);
+
+            // A full frame needs the 4-byte length prefix plus that many
+            // more bytes (`[type][compressed data]`).
+            let have_full_frame = buffer.len() >= 4 && {
+                let total_len = u32::from_be_bytes(buffer[0..4].try_into().unwrap()) as usize;
+                buffer.len() >= 4 + total_len
+            };
+
+            if have_full_frame { buffers.remove(&key) } else { None }
+        };
+
+        let Some(frame) = frame else { return };
+        let total_len = u32::from_be_bytes(frame[0..4].try_into().unwrap()) as usize;
+        let Some(codec) = CompressionType::from_byte(frame[4]) else { return };
+        let Some(nbt_data) = codec.decompress(&frame[5..4 + total_len]) else { return };
+
+        let chunk_x = region_x * 32 + rel_x;
+        let chunk_z = region_z * 32 + rel_z;
+        if overlay.write_chunk(chunk_x, chunk_z, &nbt_data).is_ok() {
+            self.chunk_cache.remove(&key);
+            self.record_write(region_x, region_z, chunk_index, unix_now());
+        }
+    }
+}
+
+fn unix_now() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or(0)
+}
+
+// Inode encoding: the directory is always inode 1. Every region file gets
+// an inode with the high bit set as a tag, and `(region_x, region_z)`
+// packed into the low 48 bits as two 24-bit two's-complement fields.
+const REGION_INODE_TAG: u64 = 1 << 63;
+
+fn encode_region_inode(region_x: i32, region_z: i32) -> u64 {
+    let ux = (region_x as u32) & 0x00FF_FFFF;
+    let uz = (region_z as u32) & 0x00FF_FFFF;
+    REGION_INODE_TAG | ((ux as u64) << 24) | uz as u64
+}
+
+fn decode_region_inode(ino: u64) -> Option<(i32, i32)> {
+    if ino & REGION_INODE_TAG == 0 {
+        return None;
+    }
+    let payload = ino & !REGION_INODE_TAG;
+    let ux = ((payload >> 24) & 0x00FF_FFFF) as u32;
+    let uz = (payload & 0x00FF_FFFF) as u32;
+    Some((sign_extend_24(ux), sign_extend_24(uz)))
+}
+
+fn sign_extend_24(value: u32) -> i32 {
+    if value & 0x0080_0000 != 0 {
+        (value | 0xFF00_0000) as i32
+    } else {
+        value as i32
+    }
+}
+
+fn region_filename(region_x: i32, region_z: i32) -> String {
+    format!("r.{}.{}.mca", region_x, region_z)
+}
+
+fn parse_region_filename(name: &str) -> Option<(i32, i32)> {
+    let rest = name.strip_prefix("r.")?.strip_suffix(".mca")?;
+    let mut parts = rest.splitn(2, '.');
+    let region_x = parts.next()?.parse::<i32>().ok()?;
+    let region_z = parts.next()?.parse::<i32>().ok()?;
+    Some((region_x, region_z))
+}
+
+fn region_file_attr(ino: u64, size: u64) -> FileAttr {
+    let mut attr = FILE_ATTR_TEMPLATE;
+    attr.ino = ino;
+    attr.size = size;
+    attr.blocks = (size + 511) / 512;
+    attr
+}
 
 const DIR_ATTR_TEMPLATE: FileAttr = FileAttr {
     ino: 1,
@@ -36,8 +274,8 @@ const DIR_ATTR_TEMPLATE: FileAttr = FileAttr {
 
 const FILE_ATTR_TEMPLATE: FileAttr = FileAttr {
     ino: 2,
-    size: 8192 + (32 * 32 * 64 * 4096), // Header + Data
-    blocks: 8, // Non-zero blocks count to show it exists
+    size: 0, // overwritten with the region's real packed size in `region_file_attr`
+    blocks: 0, // overwritten alongside `size`
     atime: UNIX_EPOCH,
     mtime: UNIX_EPOCH,
     ctime: UNIX_EPOCH,
@@ -51,41 +289,47 @@ const FILE_ATTR_TEMPLATE: FileAttr = FileAttr {
 impl Filesystem for McFUSE {
     // 1. GETATTR (File attributes)
     fn getattr(&mut self, req: &Request, ino: u64, _fh: Option<u64>, reply: fuser::ReplyAttr) {
-        match ino {
-            1 => { // Directory
-                let mut attr = DIR_ATTR_TEMPLATE;
-                attr.uid = req.uid(); attr.gid = req.gid();
-                reply.attr(&Duration::from_secs(1), &attr);
-            },
-            2 => { // Our file r.0.0.mca
-                let mut attr = FILE_ATTR_TEMPLATE;
-                attr.uid = req.uid(); attr.gid = req.gid();
-                reply.attr(&Duration::from_secs(1), &attr);
-            },
-            _ => reply.error(ENOENT),
+        if ino == 1 {
+            let mut attr = DIR_ATTR_TEMPLATE;
+            attr.uid = req.uid(); attr.gid = req.gid();
+            reply.attr(&Duration::from_secs(1), &attr);
+        } else if let Some((region_x, region_z)) = decode_region_inode(ino) {
+            let layout = self.layout_for(region_x, region_z);
+            let mut attr = region_file_attr(ino, layout.total_size());
+            attr.uid = req.uid(); attr.gid = req.gid();
+            reply.attr(&Duration::from_secs(1), &attr);
+        } else {
+            reply.error(ENOENT);
         }
     }
 
     // 1.5 ACCESS (Check permissions)
     fn access(&mut self, _req: &Request, ino: u64, _mask: i32, reply: fuser::ReplyEmpty) {
         // We allow everything for everyone (POC)
-        if ino == 1 || ino == 2 {
+        if ino == 1 || decode_region_inode(ino).is_some() {
             reply.ok();
         } else {
             reply.error(ENOENT);
         }
     }
 
-    // 2. LOOKUP (Name search: "What is the inode for r.0.0.mca?")
+    // 2. LOOKUP (Name search: "What is the inode for r.X.Z.mca?")
     fn lookup(&mut self, req: &Request, parent: u64, name: &OsStr, reply: fuser::ReplyEntry) {
-        if parent == 1 && name.to_str() == Some("r.0.0.mca") {
-            let mut attr = FILE_ATTR_TEMPLATE;
-            attr.uid = req.uid(); attr.gid = req.gid();
-            // Generation = 0 (file version), TTL = 1 sec
-            reply.entry(&Duration::from_secs(1), &attr, 0);
-        } else {
+        let Some((region_x, region_z)) = name.to_str().and_then(parse_region_filename) else {
+            reply.error(ENOENT);
+            return;
+        };
+
+        if parent != 1 {
             reply.error(ENOENT);
+            return;
         }
+
+        let layout = self.layout_for(region_x, region_z);
+        let mut attr = region_file_attr(encode_region_inode(region_x, region_z), layout.total_size());
+        attr.uid = req.uid(); attr.gid = req.gid();
+        // Generation = 0 (file version), TTL = 1 sec
+        reply.entry(&Duration::from_secs(1), &attr, 0);
     }
 
     // 3. READDIR (LS: "What is inside the folder?")
@@ -98,17 +342,26 @@ impl Filesystem for McFUSE {
         // offset - is the cursor. FUSE can read the directory in chunks.
         // We return: (inode, type, name).
         // Important: offset increases by 1 for each subsequent entry.
-        let entries = vec![
-            (1, FileType::Directory, "."),
-            (1, FileType::Directory, ".."),
-            (2, FileType::RegularFile, "r.0.0.mca"),
+        let mut entries = vec![
+            (1, FileType::Directory, ".".to_string()),
+            (1, FileType::Directory, "..".to_string()),
         ];
 
+        for region_z in -self.region_radius..=self.region_radius {
+            for region_x in -self.region_radius..=self.region_radius {
+                entries.push((
+                    encode_region_inode(region_x, region_z),
+                    FileType::RegularFile,
+                    region_filename(region_x, region_z),
+                ));
+            }
+        }
+
         for (i, entry) in entries.into_iter().enumerate() {
             // i + 1, because offset 0 implies "start", and the next entry will be 1, 2, 3...
             if i as i64 >= offset {
                 // add returns true if the buffer is full.
-                if reply.add(entry.0, (i + 1) as i64, entry.1, entry.2) {
+                if reply.add(entry.0, (i + 1) as i64, entry.1, &entry.2) {
                     break;
                 }
             }
@@ -116,26 +369,44 @@ impl Filesystem for McFUSE {
         reply.ok();
     }
 
-    // 4. WRITE (Write into void)
+    // 4. WRITE (assemble chunk frames and persist them into the overlay)
     fn write(
         &mut self,
         _req: &Request,
         ino: u64,
         _fh: u64,
-        _offset: i64,
+        offset: i64,
         data: &[u8],
         _write_flags: u32,
         _flags: i32,
         _lock_owner: Option<u64>,
         reply: fuser::ReplyWrite,
     ) {
-        if ino == 2 {
-            // "Honestly" say that we wrote as many bytes as sent
-            println!("Writing {} dummy bytes to inode {}", data.len(), ino);
-            reply.written(data.len() as u32);
-        } else {
+        let Some((region_x, region_z)) = decode_region_inode(ino) else {
             reply.error(ENOENT);
+            return;
+        };
+
+        let written = data.len() as u32;
+        let offset = offset as u64;
+
+        // The header is always synthesized from the layout and timestamp
+        // table on read, so there's nothing to persist here.
+        if offset < region::HEADER_BYTES {
+            reply.written(written);
+            return;
         }
+
+        let layout = self.layout_for(region_x, region_z);
+        let Some((chunk_index, local_offset)) = layout.chunk_at_offset(offset) else {
+            // Past the end of this region's current layout — nothing
+            // meaningful to persist (e.g. sector padding).
+            reply.written(written);
+            return;
+        };
+
+        self.assemble_and_persist_write(region_x, region_z, chunk_index, local_offset, data);
+        reply.written(written);
     }
 
     // 5. READ (The core logic)
@@ -150,46 +421,31 @@ impl Filesystem for McFUSE {
         _lock_owner: Option<u64>,
         reply: fuser::ReplyData,
     ) {
-        if ino != 2 {
+        let Some((region_x, region_z)) = decode_region_inode(ino) else {
             reply.data(&[]);
             return;
-        }
+        };
 
         let offset = offset as u64;
         let size = size as usize;
         let mut response_data = Vec::with_capacity(size);
 
+        let layout = self.layout_for(region_x, region_z);
+
         // --- 1. HEADER GENERATION (0..8192) ---
         // If the request overlaps the header
-        if offset < 8192 {
-            let mut header = vec![0u8; 8192];
-            for i in 0..1024 {
-                let rel_x = i % 32;
-                let rel_z = i / 32;
-                
-                // Calculate where the chunk lies using our Sparse formula
-                let chunk_offset = region::get_chunk_file_offset(rel_x, rel_z);
-                let sector_id = (chunk_offset / 4096) as u32;
-                let sector_count = region::SECTORS_PER_CHUNK as u8;
-
-                // Minecraft stores: [Offset:3 bytes][Count:1 byte] (Big Endian)
-                let loc_idx = (i as usize) * 4;
-                header[loc_idx] = ((sector_id >> 16) & 0xFF) as u8;
-                header[loc_idx + 1] = ((sector_id >> 8) & 0xFF) as u8;
-                header[loc_idx + 2] = (sector_id & 0xFF) as u8;
-                header[loc_idx + 3] = sector_count;
-            }
-            
+        if offset < region::HEADER_BYTES {
+            let timestamps = self.timestamps_for(region_x, region_z);
+            let header = region::Header::generate(layout.locations(), &timestamps);
+
             // Copy the requested part of the header into the response
             let start_in_header = offset as usize;
-            let end_in_header = std::cmp::min(start_in_header + size, 8192);
-            if start_in_header < 8192 {
+            let end_in_header = std::cmp::min(start_in_header + size, region::HEADER_BYTES as usize);
+            if start_in_header < region::HEADER_BYTES as usize {
                 response_data.extend_from_slice(&header[start_in_header..end_in_header]);
             }
         }
 
-        // --- 2. CHUNK DATA GENERATION (8192+) ---
-        // If we need to fill the rest of the buffer with chunk data
         // --- 2. CHUNK DATA GENERATION (8192+) ---
         // Loop until we filled the buffer or confirmed we are out of bounds
         while response_data.len() < size {
@@ -197,61 +453,54 @@ impl Filesystem for McFUSE {
             let data_read_offset = offset + current_len as u64;
             let needed = size - current_len;
 
-            // Determine which chunk we hit
-            if let Some((rel_x, rel_z)) = region::get_chunk_coords_from_offset(data_read_offset) {
-                // Generate chunk!
-                // Note: In a real system, we should cache this, but for now we regenerate.
-                // Because we use deterministic generation, it is safe.
-                if let Ok(nbt_data) = self.generator.generate_chunk(rel_x, rel_z) {
-                    // Compress (Zlib)
-                    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
-                    if encoder.write_all(&nbt_data).is_ok() {
-                        if let Ok(compressed) = encoder.finish() {
-                            
-                            // Form the chunk "Packet": [Length: 4][Type: 1][Data...]
-                            let total_len = (compressed.len() + 1) as u32; // +1 byte for Type
-                            let mut chunk_blob = Vec::new();
-                            chunk_blob.extend_from_slice(&total_len.to_be_bytes()); // Big Endian Length
-                            chunk_blob.push(2); // Type 2 = Zlib
-                            chunk_blob.extend_from_slice(&compressed);
-
-                            let chunk_start_file_offset = region::get_chunk_file_offset(rel_x, rel_z);
-                            
-                            // Which part of this blob do we need?
-                            if data_read_offset >= chunk_start_file_offset {
-                                let local_offset = (data_read_offset - chunk_start_file_offset) as usize;
-                                
-                                if local_offset < chunk_blob.len() {
-                                    let available = chunk_blob.len() - local_offset;
-                                    let to_copy = std::cmp::min(available, needed);
-                                    response_data.extend_from_slice(&chunk_blob[local_offset..local_offset + to_copy]);
-                                    continue; // We made progress
-                                } else {
-                                    // We are reading past the actual data of this chunk (Sparse Void)
-                                    // Can we skip fast?
-                                    // The chunk allocates 256KB (SECTORS_PER_CHUNK * 4096). 
-                                    // We are in the "Padding" zone of this chunk.
-                                    // We should fill zeros until end of this chunk or end of request.
-                                    
-                                    let chunk_end_offset = chunk_start_file_offset + (region::SECTORS_PER_CHUNK as u64 * 4096);
-                                    let zeros_available = chunk_end_offset.saturating_sub(data_read_offset);
-                                    let zeros_to_give = std::cmp::min(zeros_available as usize, needed);
-                                    
-                                    // Efficient zero filling
-                                    response_data.resize(current_len + zeros_to_give, 0);
-                                    continue;
-                                }
-                            }
-                        }
+            // Find which densely-packed chunk this file offset falls inside.
+            if let Some((chunk_index, local_offset)) = layout.chunk_at_offset(data_read_offset) {
+                let (rel_x, rel_z) = region::rel_coords(chunk_index);
+                let chunk_x = region_x * 32 + rel_x;
+                let chunk_z = region_z * 32 + rel_z;
+
+                let cache_key = (region_x, region_z, rel_x, rel_z);
+                let chunk_blob = self.chunk_cache.get(&cache_key).or_else(|| {
+                    let blob = region::compress_chunk(
+                        self.generator.as_ref(),
+                        self.overlay.as_deref().map(|o| o as &dyn ChunkStorage),
+                        chunk_x,
+                        chunk_z,
+                        self.compression,
+                    )?;
+                    let blob = Arc::new(blob);
+                    self.chunk_cache.insert(cache_key, blob.clone());
+                    Some(blob)
+                });
+
+                if let Some(chunk_blob) = chunk_blob {
+                    if local_offset < chunk_blob.len() as u64 {
+                        let local_offset = local_offset as usize;
+                        let available = chunk_blob.len() - local_offset;
+                        let to_copy = std::cmp::min(available, needed);
+                        response_data.extend_from_slice(&chunk_blob[local_offset..local_offset + to_copy]);
+                        continue; // We made progress
+                    } else {
+                        // Reading past the blob but still inside its
+                        // rounded-up sector padding — fill zeros to the end
+                        // of this chunk's allocation.
+                        let (sector_offset, sector_count) = layout.locations()[chunk_index];
+                        let chunk_end_offset =
+                            (sector_offset + sector_count as u32) as u64 * region::SECTOR_BYTES;
+                        let zeros_available = chunk_end_offset.saturating_sub(data_read_offset);
+                        let zeros_to_give = std::cmp::min(zeros_available as usize, needed);
+
+                        response_data.resize(current_len + zeros_to_give, 0);
+                        continue;
                     }
                 }
             }
-            
+
             // If we are here, we failed to map to a chunk (EOF or Error) or Generation Failed
             // Stop loop to avoid infinite loop
             break;
         }
-        
+
         // Pad with zeros if something is missing (Sparse emptiness)
         if response_data.len() < size {
             response_data.resize(size, 0);
@@ -259,4 +508,99 @@ impl Filesystem for McFUSE {
 
         reply.data(&response_data);
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap as StdHashMap;
+    use std::sync::Mutex as StdMutex;
+
+    struct DummyGenerator;
+    impl WorldGenerator for DummyGenerator {
+        fn generate_chunk(&self, _x: i32, _z: i32) -> anyhow::Result<Vec<u8>> {
+            anyhow::bail!("not used by these tests")
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryStorage {
+        chunks: StdMutex<StdHashMap<(i32, i32), Vec<u8>>>,
+    }
+
+    impl ChunkStorage for MemoryStorage {
+        fn read_chunk(&self, chunk_x: i32, chunk_z: i32) -> anyhow::Result<Option<Vec<u8>>> {
+            Ok(self.chunks.lock().unwrap().get(&(chunk_x, chunk_z)).cloned())
+        }
+
+        fn write_chunk(&self, chunk_x: i32, chunk_z: i32, data: &[u8]) -> anyhow::Result<()> {
+            self.chunks.lock().unwrap().insert((chunk_x, chunk_z), data.to_vec());
+            Ok(())
+        }
+    }
+
+    // `[length:4][type:1][data:N]`, uncompressed, so the payload round-trips
+    // byte for byte.
+    fn frame(nbt: &[u8]) -> Vec<u8> {
+        let total_len = (nbt.len() + 1) as u32;
+        let mut frame = Vec::with_capacity(5 + nbt.len());
+        frame.extend_from_slice(&total_len.to_be_bytes());
+        frame.push(CompressionType::Uncompressed.type_byte());
+        frame.extend_from_slice(nbt);
+        frame
+    }
+
+    fn fuse_with_overlay() -> (McFUSE, Arc<MemoryStorage>) {
+        let overlay = Arc::new(MemoryStorage::default());
+        let fuse = McFUSE::with_overlay(Arc::new(DummyGenerator), overlay.clone());
+        (fuse, overlay)
+    }
+
+    #[test]
+    fn test_full_frame_delivered_in_one_write_persists_to_the_overlay() {
+        let (fuse, overlay) = fuse_with_overlay();
+        let payload = frame(b"raw chunk nbt");
+
+        fuse.assemble_and_persist_write(0, 0, 0, 0, &payload);
+
+        assert_eq!(overlay.read_chunk(0, 0).unwrap(), Some(b"raw chunk nbt".to_vec()));
+    }
+
+    #[test]
+    fn test_frame_split_across_two_writes_persists_only_once_complete() {
+        let (fuse, overlay) = fuse_with_overlay();
+        let payload = frame(b"split across two writes");
+        let (first_half, second_half) = payload.split_at(6);
+
+        fuse.assemble_and_persist_write(0, 0, 0, 0, first_half);
+        assert_eq!(overlay.read_chunk(0, 0).unwrap(), None, "partial frame must not persist yet");
+
+        fuse.assemble_and_persist_write(0, 0, 0, 6, second_half);
+        assert_eq!(
+            overlay.read_chunk(0, 0).unwrap(),
+            Some(b"split across two writes".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_persisting_a_write_records_its_timestamp_and_evicts_the_cached_layout() {
+        let (fuse, _overlay) = fuse_with_overlay();
+        assert_eq!(fuse.timestamps_for(0, 0)[5], 0);
+
+        // Prime the cached layout so we can observe it being invalidated.
+        fuse.layout_for(0, 0);
+        assert!(fuse.region_layouts.read().unwrap().contains_key(&(0, 0)));
+
+        fuse.assemble_and_persist_write(0, 0, 5, 0, &frame(b"chunk five"));
+
+        assert_ne!(fuse.timestamps_for(0, 0)[5], 0);
+        assert!(!fuse.region_layouts.read().unwrap().contains_key(&(0, 0)));
+    }
+
+    #[test]
+    fn test_without_an_overlay_write_is_a_silent_no_op() {
+        let fuse = McFUSE::new(Arc::new(DummyGenerator));
+        fuse.assemble_and_persist_write(0, 0, 0, 0, &frame(b"nowhere to go"));
+        assert_eq!(fuse.timestamps_for(0, 0)[0], 0);
+    }
 }
\ No newline at end of file
@@ -2,6 +2,10 @@ use std::collections::HashMap;
 // Removed: use crate::chunk::{ChunkRoot, Section, BlockStates, BlockState, Biomes}; // Correct path?
 // Check imports in src/chunk.rs once creating. Assuming crate::chunk for now as per previous edits.
 
+// Chunk's Y range: -64..320 (384 blocks tall).
+const MIN_WORLD_Y: i32 = -64;
+const WORLD_HEIGHT: i32 = 384;
+
 #[derive(Default)]
 pub struct ChunkBuilder {
     // We store blocks in a sparse map for simplicity in MVP.
@@ -9,10 +13,15 @@ pub struct ChunkBuilder {
     // This isn't the most efficient (VoxelGrid is faster), but it's the easiest to write "set_block".
     // For full layers we will handle efficient filling during build().
     custom_blocks: HashMap<(u8, i32, u8), String>,
-    
+
     // Optimisation for layers:
     // Key: y, Value: Block Name
     full_layers: HashMap<i32, String>,
+
+    // Optional world template: when set, overrides the per-column height
+    // used for the MOTION_BLOCKING/WORLD_SURFACE heightmaps instead of
+    // deriving it from whatever blocks happen to be set below.
+    terrain_template: Option<Box<dyn Fn(u8, u8) -> i32 + Send + Sync>>,
 }
 
 impl ChunkBuilder {
@@ -34,6 +43,89 @@ impl ChunkBuilder {
         self.custom_blocks.retain(|(_, by, _), _| *by != y);
     }
 
+    /// Supply a world template giving the surface Y for each chunk-local
+    /// `(x, z)` column. When set, this drives the generated heightmaps
+    /// directly instead of them being inferred from `custom_blocks`/
+    /// `full_layers`, which matters once terrain isn't just flat layers.
+    pub fn set_heightmap_fn<F>(&mut self, f: F)
+    where
+        F: Fn(u8, u8) -> i32 + Send + Sync + 'static,
+    {
+        self.terrain_template = Some(Box::new(f));
+    }
+
+    fn is_air_block(name: &str) -> bool {
+        matches!(name, "minecraft:air" | "minecraft:cave_air" | "minecraft:void_air")
+    }
+
+    // Per-column (x + z*16) surface height: the terrain template if one was
+    // given, otherwise the Y of the first free space above the highest
+    // non-air block actually set at that column.
+    fn compute_heights(&self) -> [i32; 256] {
+        let mut heights = [MIN_WORLD_Y; 256];
+
+        if let Some(template) = &self.terrain_template {
+            for z in 0u8..16 {
+                for x in 0u8..16 {
+                    heights[x as usize + z as usize * 16] = template(x, z);
+                }
+            }
+            return heights;
+        }
+
+        let mut layer_ys: Vec<i32> = self.full_layers.keys().copied().collect();
+        layer_ys.sort_unstable_by(|a, b| b.cmp(a));
+
+        for z in 0u8..16 {
+            for x in 0u8..16 {
+                let mut column_ys = layer_ys.clone();
+                for &(bx, by, bz) in self.custom_blocks.keys() {
+                    if bx == x && bz == z && !column_ys.contains(&by) {
+                        column_ys.push(by);
+                    }
+                }
+                column_ys.sort_unstable_by(|a, b| b.cmp(a));
+
+                for y in column_ys {
+                    let name = self
+                        .custom_blocks
+                        .get(&(x, y, z))
+                        .or_else(|| self.full_layers.get(&y));
+                    if let Some(name) = name {
+                        if !Self::is_air_block(name) {
+                            heights[x as usize + z as usize * 16] = y + 1;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        heights
+    }
+
+    // Packs 256 per-column values into Minecraft's heightmap long-array
+    // encoding: `ceil(log2(world_height))` bits per entry, with entries
+    // never allowed to straddle an `i64` boundary (the same rule `nbt.rs`
+    // uses for palette data).
+    fn pack_heightmap(heights: &[i32; 256]) -> Vec<i64> {
+        let bits_per_entry = 32 - ((WORLD_HEIGHT - 1) as u32).leading_zeros();
+        let entries_per_long = (64 / bits_per_entry) as usize;
+        let mask: i64 = (1i64 << bits_per_entry) - 1;
+
+        let longs_needed = (256 + entries_per_long - 1) / entries_per_long;
+        let mut longs = vec![0i64; longs_needed];
+
+        for (i, &height) in heights.iter().enumerate() {
+            let value = (height - MIN_WORLD_Y) as i64 & mask;
+            let long_index = i / entries_per_long;
+            let slot = i % entries_per_long;
+            longs[long_index] |= value << (slot as u32 * bits_per_entry);
+        }
+
+        longs
+    }
+
     pub fn build(self, chunk_x: i32, chunk_z: i32) -> anyhow::Result<Vec<u8>> {
         use pumpkin_world::chunk::{ChunkData, ChunkSections, SubChunk, ChunkHeightmaps, ChunkLight};
         use pumpkin_world::chunk::format::LightContainer;
@@ -74,9 +166,17 @@ impl ChunkBuilder {
         }
 
         // 4. Construct ChunkData
+        let heights = self.compute_heights();
+        let motion_blocking = Self::pack_heightmap(&heights);
+        let world_surface = motion_blocking.clone();
+
         let chunk_data = ChunkData {
             section: sections,
-            heightmap: ChunkHeightmaps::default(), // TODO: Calculate?
+            heightmap: ChunkHeightmaps {
+                motion_blocking,
+                world_surface,
+                ..Default::default()
+            },
             x: chunk_x,
             z: chunk_z,
             block_ticks: Default::default(),
@@ -0,0 +1,19 @@
+//! World generation.
+//!
+//! A [`WorldGenerator`] produces raw (uncompressed) chunk NBT bytes on
+//! demand — the same shape a `ChunkStorage` backend hands back for a chunk
+//! it already had on disk, so FUSE and region layout code can treat
+//! "generate" and "load" interchangeably behind `Arc<dyn WorldGenerator>`
+//! and apply compression and Anvil framing themselves.
+
+pub mod builder;
+mod flat;
+
+pub use flat::FlatGenerator;
+
+/// Produces chunk data procedurally for coordinates storage doesn't have.
+pub trait WorldGenerator: Send + Sync {
+    /// Generate a chunk at the given absolute chunk coordinates.
+    /// Returns the chunk's raw, uncompressed NBT bytes (no `[length][type]` frame).
+    fn generate_chunk(&self, x: i32, z: i32) -> anyhow::Result<Vec<u8>>;
+}
@@ -1,6 +1,13 @@
 // --- IMPORTS ---
 // We attach the module we just created
 mod nbt_structs;
+mod nbt;
+mod storage;
+mod chunk;
+mod block_registry;
+mod generator;
+mod region;
+mod fuse;
 
 use crate::nbt_structs::*;
 use fuser::{
@@ -10,11 +17,14 @@ use fuser::{
 use libc::ENOENT; // "Error No Entry" - standard Linux error for "File not found"
 use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::io::Write;
-use std::sync::Mutex;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::time::{Duration, UNIX_EPOCH};
-use flate2::write::ZlibEncoder;
+use flate2::write::{GzEncoder, ZlibEncoder};
 use flate2::Compression;
+use quick_cache::sync::Cache;
 
 // --- CONSTANTS ---
 // How long the Kernel should cache file attributes.
@@ -28,21 +38,661 @@ const TTL: Duration = Duration::from_secs(1);
 const HEADER_SIZE: u64 = 8192;
 const CHUNK_PADDING: u64 = 4096; // We virtually align every chunk to 4KB
 
+// A chunk whose compressed payload needs more than this many sectors can no
+// longer fit the 1-byte sector count in the location table and must be
+// spilled into a sibling `c.X.Z.mcc` file instead (real Anvil limit).
+const MAX_INLINE_SECTORS: u64 = 255;
+// External-chunk marker: high bit of the compression byte (real Anvil flag).
+const EXTERNAL_FLAG: u8 = 0x80;
+
+// --- COMPRESSION ---
+// The Anvil chunk header's 1-byte compression id. Minecraft has accepted all
+// four of these since 1.20.5 (LZ4 is also used internally for `.linear` files).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CompressionType {
+    Gzip = 1,
+    Zlib = 2,
+    Uncompressed = 3,
+    Lz4 = 4,
+}
+
+impl CompressionType {
+    fn type_byte(self) -> u8 {
+        self as u8
+    }
+
+    // Compress `data` with this codec. Returns the compressed bytes only —
+    // callers add the `[length][type]` framing.
+    fn compress(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            CompressionType::Zlib => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data).unwrap();
+                encoder.finish().unwrap()
+            }
+            CompressionType::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data).unwrap();
+                encoder.finish().unwrap()
+            }
+            CompressionType::Uncompressed => data.to_vec(),
+            CompressionType::Lz4 => lz4_flex::compress_prepend_size(data),
+        }
+    }
+}
+
+// --- BACKING STORE ---
+// When `AnvilFS::backing_dir` is set, regions are served from real `r.X.Z.mca`
+// files on disk (falling back to procedural generation only for chunks that
+// are missing or fail validation), and writes persist back to those files.
+// Without a backing directory the filesystem behaves exactly as before:
+// everything is synthesized and writes are discarded.
+
+// Reads a chunk's raw `[len][type][data]` frame straight out of a real
+// region file's location table, or `None` if the chunk was never saved.
+fn read_backing_chunk(path: &Path, rel_x: i32, rel_z: i32) -> Option<Vec<u8>> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let index = ((rel_x & 31) + (rel_z & 31) * 32) as u64;
+
+    let mut loc = [0u8; 4];
+    file.seek(SeekFrom::Start(index * 4)).ok()?;
+    file.read_exact(&mut loc).ok()?;
+
+    let sector_offset = ((loc[0] as u32) << 16) | ((loc[1] as u32) << 8) | loc[2] as u32;
+    let sector_count = loc[3] as u32;
+    if sector_offset == 0 || sector_count == 0 {
+        return None;
+    }
+
+    file.seek(SeekFrom::Start(sector_offset as u64 * CHUNK_PADDING)).ok()?;
+    let mut len_buf = [0u8; 4];
+    file.read_exact(&mut len_buf).ok()?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+    if len == 0 || len > sector_count as usize * CHUNK_PADDING as usize {
+        return None;
+    }
+
+    let mut rest = vec![0u8; len];
+    file.read_exact(&mut rest).ok()?;
+
+    let mut frame = Vec::with_capacity(4 + len);
+    frame.extend_from_slice(&len_buf);
+    frame.extend_from_slice(&rest);
+    Some(frame)
+}
+
+// Finds the byte offset right after a top-level `TAG_List` field's name,
+// the same way `find_int_tag_offset` locates a `TAG_Int`.
+fn find_list_tag_offset(data: &[u8], name: &str) -> Option<usize> {
+    let mut needle = Vec::with_capacity(3 + name.len());
+    needle.push(0x09u8); // TAG_List
+    needle.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    needle.extend_from_slice(name.as_bytes());
+
+    data.windows(needle.len())
+        .position(|w| w == needle.as_slice())
+}
+
+// Decompresses a stored chunk frame and checks it has the tags a chunk needs
+// to be loadable at all: `DataVersion`/`xPos`/`zPos`/`sections`. Used to
+// decide whether a backing-store chunk can be served as-is or must be
+// treated as corrupt (deleted/regenerated).
+fn validate_chunk_frame(frame: &[u8]) -> bool {
+    if frame.len() < 5 {
+        return false;
+    }
+    let compression_byte = frame[4] & !EXTERNAL_FLAG;
+    let compressed = &frame[5..];
+
+    let decompressed: Option<Vec<u8>> = match compression_byte {
+        1 => {
+            let mut out = Vec::new();
+            flate2::read::GzDecoder::new(compressed)
+                .read_to_end(&mut out)
+                .ok()
+                .map(|_| out)
+        }
+        2 => {
+            let mut out = Vec::new();
+            flate2::read::ZlibDecoder::new(compressed)
+                .read_to_end(&mut out)
+                .ok()
+                .map(|_| out)
+        }
+        3 => Some(compressed.to_vec()),
+        4 => lz4_flex::decompress_size_prepended(compressed).ok(),
+        _ => None,
+    };
+
+    let Some(data) = decompressed else {
+        return false;
+    };
+
+    find_int_tag_offset(&data, "DataVersion").is_some()
+        && find_int_tag_offset(&data, "xPos").is_some()
+        && find_int_tag_offset(&data, "zPos").is_some()
+        && find_list_tag_offset(&data, "sections").is_some()
+}
+
+// Writes `data` at `offset` into a backing region file, creating/extending
+// it as needed. This is a plain `pwrite` — the caller is responsible for
+// invalidating any cached `RegionLayout` so later reads see the change.
+fn write_backing_bytes(path: &Path, offset: u64, data: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .read(true)
+        .write(true)
+        .open(path)?;
+
+    let needed_len = offset + data.len() as u64;
+    if file.metadata()?.len() < needed_len {
+        file.set_len(needed_len)?;
+    }
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(data)
+}
+
+// Validation/repair pass: any chunk whose stored frame fails
+// `validate_chunk_frame` is deleted by zeroing its location-table entry, so
+// the next access regenerates it procedurally instead of serving garbage.
+// Returns the number of chunks repaired.
+fn repair_region_file(path: &Path) -> std::io::Result<usize> {
+    let mut file = std::fs::OpenOptions::new().read(true).write(true).open(path)?;
+    let mut repaired = 0usize;
+
+    for index in 0..1024u64 {
+        let mut loc = [0u8; 4];
+        file.seek(SeekFrom::Start(index * 4))?;
+        file.read_exact(&mut loc)?;
+
+        let sector_offset = ((loc[0] as u32) << 16) | ((loc[1] as u32) << 8) | loc[2] as u32;
+        let sector_count = loc[3] as u32;
+        if sector_offset == 0 || sector_count == 0 {
+            continue;
+        }
+
+        let rel_x = (index % 32) as i32;
+        let rel_z = (index / 32) as i32;
+        let valid = read_backing_chunk(path, rel_x, rel_z)
+            .map(|frame| validate_chunk_frame(&frame))
+            .unwrap_or(false);
+
+        if !valid {
+            file.seek(SeekFrom::Start(index * 4))?;
+            file.write_all(&[0u8; 4])?;
+            repaired += 1;
+        }
+    }
+
+    Ok(repaired)
+}
+
+// Compaction: rewrites a region file so every live chunk's sectors are
+// contiguous from the front (sector 0-1 stay the header), eliminating the
+// gaps left behind as chunks shrink and grow across saves, then truncates
+// the trailing free space.
+fn compact_region_file(path: &Path) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().read(true).write(true).open(path)?;
+
+    let mut header = vec![0u8; HEADER_SIZE as usize];
+    file.seek(SeekFrom::Start(0))?;
+    file.read_exact(&mut header)?;
+
+    let mut live_chunks: Vec<(usize, Vec<u8>)> = Vec::new();
+    for index in 0..1024usize {
+        let loc_start = index * 4;
+        let sector_offset = ((header[loc_start] as u32) << 16)
+            | ((header[loc_start + 1] as u32) << 8)
+            | header[loc_start + 2] as u32;
+        let sector_count = header[loc_start + 3] as u32;
+        if sector_offset == 0 || sector_count == 0 {
+            continue;
+        }
+
+        file.seek(SeekFrom::Start(sector_offset as u64 * CHUNK_PADDING))?;
+        let mut raw = vec![0u8; sector_count as usize * CHUNK_PADDING as usize];
+        file.read_exact(&mut raw)?;
+        live_chunks.push((index, raw));
+    }
+
+    let mut new_header = vec![0u8; HEADER_SIZE as usize];
+    let mut body = Vec::new();
+    let mut next_sector = 2u32;
+
+    for (index, raw) in &live_chunks {
+        let sector_count = (raw.len() as u64 / CHUNK_PADDING) as u32;
+        let loc_start = index * 4;
+        new_header[loc_start] = ((next_sector >> 16) & 0xFF) as u8;
+        new_header[loc_start + 1] = ((next_sector >> 8) & 0xFF) as u8;
+        new_header[loc_start + 2] = (next_sector & 0xFF) as u8;
+        new_header[loc_start + 3] = sector_count as u8;
+
+        body.extend_from_slice(raw);
+        next_sector += sector_count;
+    }
+
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&new_header)?;
+    file.write_all(&body)?;
+    file.sync_all()
+}
+
+// How many chunk-generation jobs are allowed to run at once. Bounds memory
+// blowup when a server mmaps and touches an entire region in one go, while
+// still letting independent regions proceed in parallel.
+const MAX_CONCURRENT_GENERATION: usize = 8;
+
+// A classic counting semaphore built on Condvar. Used instead of a crate
+// dependency since this is the only place we need bounded concurrency.
+struct Semaphore {
+    permits: Mutex<usize>,
+    available: Condvar,
+}
+
+impl Semaphore {
+    fn new(permits: usize) -> Self {
+        Semaphore {
+            permits: Mutex::new(permits),
+            available: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) {
+        let mut permits = self.permits.lock().unwrap();
+        while *permits == 0 {
+            permits = self.available.wait(permits).unwrap();
+        }
+        *permits -= 1;
+    }
+
+    fn release(&self) {
+        let mut permits = self.permits.lock().unwrap();
+        *permits += 1;
+        self.available.notify_one();
+    }
+}
+
+// --- GENERATION CACHE ---
+// Up to this many distinct chunk "shapes" are kept ready to stamp out a new
+// blob for. The current flat generator only ever produces one shape, but the
+// cache is sized for a terrain generator with real variety.
+const GENERATION_CACHE_CAPACITY: usize = 256;
+
+// A compact descriptor of a chunk's *content*, independent of where in the
+// world it sits. Two chunks built from the same section/palette layout hash
+// to the same signature and can share one generated body.
+#[derive(Clone, Hash, PartialEq, Eq)]
+struct ChunkSignature {
+    section_blocks: Vec<String>,
+}
+
+// The uncompressed NBT body for a signature, generated with placeholder
+// coordinates, plus where those two `TAG_Int` values live so a cache hit can
+// patch in the real coordinates without re-running chunk generation.
+struct CachedChunkBody {
+    template: Vec<u8>,
+    x_pos_offset: usize,
+    z_pos_offset: usize,
+}
+
+// Finds the byte offset of the 4-byte value of a top-level `TAG_Int` field
+// with the given name, by scanning for its raw `[type][name_len][name]`
+// encoding. fastnbt always emits compound fields in struct declaration order
+// with this exact layout, so the search is a plain byte match.
+fn find_int_tag_offset(data: &[u8], name: &str) -> Option<usize> {
+    let mut needle = Vec::with_capacity(3 + name.len());
+    needle.push(0x03u8); // TAG_Int
+    needle.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    needle.extend_from_slice(name.as_bytes());
+
+    data.windows(needle.len())
+        .position(|w| w == needle.as_slice())
+        .map(|pos| pos + needle.len())
+}
+
+// Hit/miss counters and the bounded map of generated chunk bodies. `bodies`
+// is entry-count bounded (see `GENERATION_CACHE_CAPACITY`) the same way
+// `hoppermc_fs`'s chunk cache is byte-budget bounded — both are a quick_cache
+// LRU rather than a hand-rolled one, so there's one eviction policy to trust
+// instead of two.
+struct GenerationCache {
+    bodies: Cache<ChunkSignature, Arc<CachedChunkBody>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl GenerationCache {
+    fn new() -> Self {
+        GenerationCache {
+            bodies: Cache::new(GENERATION_CACHE_CAPACITY),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    // Returns chunk NBT bytes with real coordinates patched in, generating
+    // and caching a fresh template on first sight of `signature`.
+    fn get_or_generate(
+        &self,
+        signature: ChunkSignature,
+        chunk_x: i32,
+        chunk_z: i32,
+        generate: impl FnOnce() -> Vec<u8>,
+    ) -> Vec<u8> {
+        let cached = self.bodies.get(&signature);
+
+        let body = match cached {
+            Some(body) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                body
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                let template = generate();
+                let x_pos_offset = find_int_tag_offset(&template, "xPos")
+                    .expect("generated chunk NBT always has xPos");
+                let z_pos_offset = find_int_tag_offset(&template, "zPos")
+                    .expect("generated chunk NBT always has zPos");
+                let body = Arc::new(CachedChunkBody {
+                    template,
+                    x_pos_offset,
+                    z_pos_offset,
+                });
+                self.bodies.insert(signature, body.clone());
+                body
+            }
+        };
+
+        let mut patched = body.template.clone();
+        patched[body.x_pos_offset..body.x_pos_offset + 4]
+            .copy_from_slice(&chunk_x.to_be_bytes());
+        patched[body.z_pos_offset..body.z_pos_offset + 4]
+            .copy_from_slice(&chunk_z.to_be_bytes());
+        patched
+    }
+
+    fn unique_blobs(&self) -> usize {
+        self.bodies.len()
+    }
+
+    fn stats_line(&self) -> String {
+        format!(
+            "generation cache: {} hits, {} misses, {} unique blobs",
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+            self.unique_blobs()
+        )
+    }
+}
+
+// --- HEIGHTMAPS ---
+// The chunk's lowest/total Y range, matching `y_pos`/`sections` above.
+const MIN_WORLD_Y: i32 = -64;
+const WORLD_HEIGHT: i32 = 384;
+
+fn is_air_block(name: &str) -> bool {
+    matches!(name, "minecraft:air" | "minecraft:cave_air" | "minecraft:void_air")
+}
+
+// Packs 256 per-column values (indexed `x + z*16`) into Minecraft's
+// heightmap long-array encoding: `ceil(log2(world_height))` bits per entry,
+// with entries never allowed to straddle an `i64` boundary (the same rule
+// `nbt.rs` uses for palette data).
+fn pack_heightmap(heights: &[i32; 256], min_y: i32, world_height: i32) -> Vec<i64> {
+    let bits_per_entry = 32 - ((world_height - 1) as u32).leading_zeros();
+    let entries_per_long = (64 / bits_per_entry) as usize;
+    let mask: i64 = (1i64 << bits_per_entry) - 1;
+
+    let longs_needed = (256 + entries_per_long - 1) / entries_per_long;
+    let mut longs = vec![0i64; longs_needed];
+
+    for (i, &height) in heights.iter().enumerate() {
+        let value = (height - min_y) as i64 & mask;
+        let long_index = i / entries_per_long;
+        let slot = i % entries_per_long;
+        longs[long_index] |= value << (slot as u32 * bits_per_entry);
+    }
+
+    longs
+}
+
+// Scans a chunk's per-section block assignment top-down for the first
+// non-air section and returns the Y of the first free space above it —
+// vanilla's MOTION_BLOCKING/WORLD_SURFACE convention. `section_blocks[i]`
+// is the uniform block of section Y = `i as i32 - 4` (see
+// `AnvilFS::section_block_names`).
+fn compute_column_height(section_blocks: &[String]) -> i32 {
+    for (i, name) in section_blocks.iter().enumerate().rev() {
+        if !is_air_block(name) {
+            let section_y = i as i32 - 4;
+            return section_y * 16 + 16;
+        }
+    }
+    MIN_WORLD_Y
+}
+
+// Every column shares one height today, since generation isn't yet
+// coordinate-dependent (see `AnvilFS::section_block_names`); the packing
+// still runs over all 256 columns so this keeps working once it is.
+fn build_heightmaps(section_blocks: &[String]) -> Heightmaps {
+    let height = compute_column_height(section_blocks);
+    let heights = [height; 256];
+    let packed = pack_heightmap(&heights, MIN_WORLD_Y, WORLD_HEIGHT);
+
+    Heightmaps {
+        motion_blocking: fastnbt::LongArray::new(packed.clone()),
+        world_surface: fastnbt::LongArray::new(packed),
+    }
+}
+
+// Where a chunk's encoded payload ended up once it is compressed.
+enum ChunkEncoding {
+    // Fits entirely inside the region file: the full `[len][type][data]` frame.
+    Inline(Vec<u8>),
+    // Too big for 255 sectors: a small in-region stub plus the real payload,
+    // which the caller stores for the matching `c.X.Z.mcc` inode.
+    External { stub: Vec<u8>, payload: Vec<u8> },
+}
+
+// --- REGION LAYOUT ---
+// A single chunk's placement inside the virtual .mca file.
+// `sector_count == 0` means the chunk has no data (offset is also 0, matching
+// the real Anvil convention for "never generated").
+#[derive(Clone)]
+struct ChunkSlot {
+    sector_offset: u32,
+    sector_count: u8,
+    blob: Vec<u8>,
+}
+
+// Computes and caches where every chunk in a region actually lands once blobs
+// are allowed to span more than one 4 KB sector.
+//
+// DECISION: kept as the canonical implementation for this binary, not
+// consolidated onto `region::layout::RegionLayout` (the FUSE-facing
+// equivalent, now reachable via `mod region;`/`mod fuse;`). The two solve
+// different problems: this one has to honor `AnvilFS::backing_dir` (real
+// on-disk chunks on disk take priority over generation, checked via
+// `read_backing_chunk`/`validate_chunk_frame` before falling back) and spill
+// oversized blobs into `ChunkEncoding::External` for a sibling `.mcc` file —
+// neither of which `region::layout::RegionLayout`'s `WorldGenerator`/
+// `ChunkStorage`-generic `build` supports today. Folding backing-directory
+// reads and `.mcc` spilling into the shared implementation is worth doing
+// eventually, but it's new functionality for that type, not a drop-in swap,
+// so it's out of scope for a point fix. `storage::region_file::RegionFileStorage`
+// is not a third copy of this logic at all — it's a real disk-persisted
+// `ChunkStorage` backend (mutable, incremental, vanilla-readable `.mca`
+// files), a different concern from either in-memory layout calculator; see
+// its own doc comment.
+struct RegionLayout {
+    // Indexed by `(x & 31) + (z & 31) * 32`.
+    slots: Vec<ChunkSlot>,
+    // World chunk coords -> raw payload, for chunks spilled to `.mcc` files.
+    external_payloads: HashMap<(i32, i32), Vec<u8>>,
+}
+
+impl RegionLayout {
+    // Generate every chunk touched by this region in parallel (bounded by
+    // `MAX_CONCURRENT_GENERATION`), then assign cumulative sector offsets in
+    // index order, starting right after the two 4 KB header sectors.
+    fn build(region_x: i32, region_z: i32, fs: &AnvilFS) -> Self {
+        let semaphore = Semaphore::new(MAX_CONCURRENT_GENERATION);
+        let mut encodings: Vec<Option<ChunkEncoding>> = (0..1024).map(|_| None).collect();
+        let encodings = Mutex::new(&mut encodings);
+
+        // If a backing directory is configured, real chunk data on disk wins
+        // over procedural generation; only missing/invalid chunks fall back.
+        let backing_path = fs
+            .backing_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("r.{}.{}.mca", region_x, region_z)));
+
+        std::thread::scope(|scope| {
+            for local_z in 0..32i32 {
+                for local_x in 0..32i32 {
+                    let semaphore = &semaphore;
+                    let encodings = &encodings;
+                    let backing_path = &backing_path;
+                    scope.spawn(move || {
+                        semaphore.acquire();
+                        let world_chunk_x = region_x * 32 + local_x;
+                        let world_chunk_z = region_z * 32 + local_z;
+
+                        let encoding = backing_path
+                            .as_deref()
+                            .and_then(|path| read_backing_chunk(path, local_x, local_z))
+                            .filter(|frame| validate_chunk_frame(frame))
+                            .map(ChunkEncoding::Inline)
+                            .unwrap_or_else(|| fs.encode_chunk(world_chunk_x, world_chunk_z));
+                        semaphore.release();
+
+                        let index = (local_x + local_z * 32) as usize;
+                        encodings.lock().unwrap()[index] = Some(encoding);
+                    });
+                }
+            }
+        });
+
+        let encodings = encodings.into_inner().unwrap();
+
+        let mut slots = Vec::with_capacity(1024);
+        let mut external_payloads = HashMap::new();
+        let mut next_sector = 2u32; // sectors 0-1 are the header
+
+        for (index, encoding) in encodings.drain(..).enumerate() {
+            let local_x = (index % 32) as i32;
+            let local_z = (index / 32) as i32;
+            let world_chunk_x = region_x * 32 + local_x;
+            let world_chunk_z = region_z * 32 + local_z;
+
+            let blob = match encoding.expect("every slot generated above") {
+                ChunkEncoding::Inline(bytes) => bytes,
+                ChunkEncoding::External { stub, payload } => {
+                    external_payloads.insert((world_chunk_x, world_chunk_z), payload);
+                    stub
+                }
+            };
+
+            let sector_count = ((blob.len() as u64 + CHUNK_PADDING - 1) / CHUNK_PADDING) as u8;
+            let sector_offset = next_sector;
+            next_sector += sector_count as u32;
+
+            slots.push(ChunkSlot {
+                sector_offset,
+                sector_count,
+                blob,
+            });
+        }
+
+        RegionLayout {
+            slots,
+            external_payloads,
+        }
+    }
+
+    fn slot(&self, rel_x: i32, rel_z: i32) -> &ChunkSlot {
+        let index = ((rel_x & 31) + (rel_z & 31) * 32) as usize;
+        &self.slots[index]
+    }
+
+    fn external_payload(&self, chunk_x: i32, chunk_z: i32) -> Option<&[u8]> {
+        self.external_payloads
+            .get(&(chunk_x, chunk_z))
+            .map(|v| v.as_slice())
+    }
+
+    // Total size of the virtual file: header plus every allocated sector.
+    fn file_size(&self) -> u64 {
+        let used_sectors: u32 = self.slots.iter().map(|s| s.sector_count as u32).sum();
+        HEADER_SIZE + used_sectors as u64 * CHUNK_PADDING
+    }
+}
+
+// What a looked-up/cached inode refers to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum InodeEntry {
+    // A whole `r.X.Z.mca` region file.
+    Region(i32, i32),
+    // An overflow `c.X.Z.mcc` file holding one oversized chunk's payload.
+    Mcc(i32, i32),
+}
+
 // --- THE DRIVER STRUCT ---
 struct AnvilFS {
-    // Maps inode -> (region_x, region_z)
-    // We use Mutex because FUSE callbacks need &mut self
-    inode_map: Mutex<HashMap<u64, (i32, i32)>>,
+    // Maps inode -> what it refers to (a region or an overflow .mcc file).
+    // An RwLock lets concurrent FUSE callbacks (lookup/getattr/read) share a
+    // read lock; only creating a brand-new inode needs exclusive access.
+    inode_map: RwLock<HashMap<u64, InodeEntry>>,
     // Next available inode (starts at 2, as 1 is root)
     next_inode: Mutex<u64>,
+    // Cached sector layout per region, so the location table (ZONE A) and the
+    // chunk data (ZONE C) stay consistent across the many small reads the
+    // kernel issues for the same region. RwLock so independent regions that
+    // are already cached don't serialize behind one another.
+    region_layouts: RwLock<HashMap<(i32, i32), Arc<RegionLayout>>>,
+    // Codec used to compress generated chunk payloads.
+    compression: CompressionType,
+    // Dedups generated chunk bodies by content signature, so structurally
+    // identical chunks (e.g. every all-air/all-dirt flat chunk) share one
+    // generated-and-serialized body instead of paying that cost per chunk.
+    // Arc'd so a `--stats` reporter thread can hold its own handle.
+    generation_cache: Arc<GenerationCache>,
+    // When set, regions are backed by real `.mca` files under this directory
+    // instead of being purely synthesized; see the "BACKING STORE" section.
+    backing_dir: Option<PathBuf>,
 }
 
 impl AnvilFS {
-    fn new() -> Self {
+    fn new(backing_dir: Option<PathBuf>) -> Self {
         AnvilFS {
-            inode_map: Mutex::new(HashMap::new()),
+            inode_map: RwLock::new(HashMap::new()),
             next_inode: Mutex::new(2), // 1 is reserved for root
+            region_layouts: RwLock::new(HashMap::new()),
+            compression: CompressionType::Zlib,
+            generation_cache: Arc::new(GenerationCache::new()),
+            backing_dir,
+        }
+    }
+
+    // Get (building if necessary) the cached sector layout for a region.
+    fn get_layout(&self, region_x: i32, region_z: i32) -> Arc<RegionLayout> {
+        // Fast path: shared read lock, no contention with other cached regions.
+        if let Some(layout) = self.region_layouts.read().unwrap().get(&(region_x, region_z)) {
+            return layout.clone();
         }
+
+        // Slow path: build outside the lock (it spawns its own worker
+        // threads), then take the exclusive lock only to insert.
+        let built = Arc::new(RegionLayout::build(region_x, region_z, self));
+        let mut layouts = self.region_layouts.write().unwrap();
+        layouts
+            .entry((region_x, region_z))
+            .or_insert(built)
+            .clone()
     }
 
     // Parse "r.X.Z.mca" -> Some((X, Z))
@@ -57,85 +707,155 @@ impl AnvilFS {
         }
     }
 
-    // Get or create inode for a region
-    fn get_or_create_inode(&self, region_x: i32, region_z: i32) -> u64 {
-        let mut map = self.inode_map.lock().unwrap();
+    // Parse "c.X.Z.mcc" -> Some((X, Z)) (external oversized chunk, coords are
+    // absolute chunk coordinates, matching vanilla's naming).
+    fn parse_mcc_name(name: &str) -> Option<(i32, i32)> {
+        let parts: Vec<&str> = name.split('.').collect();
+        if parts.len() == 4 && parts[0] == "c" && parts[3] == "mcc" {
+            let x = parts[1].parse::<i32>().ok()?;
+            let z = parts[2].parse::<i32>().ok()?;
+            Some((x, z))
+        } else {
+            None
+        }
+    }
+
+    // Get or create inode for a given region/mcc entry.
+    fn get_or_create_inode(&self, entry: InodeEntry) -> u64 {
+        // Fast path: most lookups are for an entry we've already assigned.
+        {
+            let map = self.inode_map.read().unwrap();
+            for (&ino, &existing) in map.iter() {
+                if existing == entry {
+                    return ino;
+                }
+            }
+        }
 
-        // Check if we already have an inode for this region
-        for (&ino, &(rx, rz)) in map.iter() {
-            if rx == region_x && rz == region_z {
+        // Slow path: take the exclusive lock to allocate a new inode,
+        // re-checking in case another thread raced us here.
+        let mut map = self.inode_map.write().unwrap();
+        for (&ino, &existing) in map.iter() {
+            if existing == entry {
                 return ino;
             }
         }
 
-        // Create new inode
         let mut next = self.next_inode.lock().unwrap();
         let ino = *next;
         *next += 1;
-        map.insert(ino, (region_x, region_z));
+        map.insert(ino, entry);
         ino
     }
 
     // --- THE CORE LOGIC: Procedural Generation ---
-    // This function runs in RAM. It creates the NBT structure -> Bytes -> Zlib -> Chunk Blob
-    fn generate_chunk_bytes(&self, chunk_x: i32, chunk_z: i32) -> Vec<u8> {
-        let mut sections = Vec::new();
-
-        // Generate sections from Y=-4 to Y=19 (Total height: 384 blocks)
-        for section_y in -4..20 {
-            // Logic: Bottom section (y=-4) is Bedrock. Everything else is Air.
-            let block_name = if section_y == -4 {
-                "minecraft:dirt"
-            } else {
-                "minecraft:air"
-            };
+    // This function runs in RAM. It creates the NBT structure, serializes it,
+    // compresses it with the configured codec, and frames/encodes it per the
+    // Anvil inline-vs-external rule.
+    fn encode_chunk(&self, chunk_x: i32, chunk_z: i32) -> ChunkEncoding {
+        let nbt_data = self.generate_chunk_nbt(chunk_x, chunk_z);
+        let compressed_data = self.compression.compress(&nbt_data);
 
-            // Create the palette.
-            // If it's bedrock, the palette is ["minecraft:bedrock"].
-            // If it's air, the palette is ["minecraft:air"].
-            let palette = vec![BlockState {
-                name: block_name.to_string(),
-            }];
-
-            sections.push(Section {
-                y: section_y as i8,
-                block_states: BlockStates { palette },
-                biomes: Biomes {
-                    // Biomes are mandatory in 1.21. We set everything to Plains.
-                    palette: vec!["minecraft:plains".to_string()],
-                },
-            });
+        // Whole inline frame: 4-byte length + 1 type byte + payload.
+        let sectors_needed = (compressed_data.len() as u64 + 5 + CHUNK_PADDING - 1) / CHUNK_PADDING;
+        if sectors_needed <= MAX_INLINE_SECTORS {
+            // 3. Wrap in MCA format: [Length (4 bytes)] + [CompressionType (1 byte)] + [Data]
+            let mut final_blob = Vec::new();
+            let total_len = (compressed_data.len() + 1) as u32; // +1 for the compression byte
+            final_blob.extend_from_slice(&total_len.to_be_bytes()); // Big Endian!
+            final_blob.push(self.compression.type_byte());
+            final_blob.extend_from_slice(&compressed_data);
+            ChunkEncoding::Inline(final_blob)
+        } else {
+            // Too large for an inline sector run: point at a `.mcc` file.
+            // The in-region stub is just the 5-byte frame header with the
+            // external flag set and no trailing data.
+            let mut stub = Vec::with_capacity(5);
+            stub.extend_from_slice(&1u32.to_be_bytes()); // length = 1 (just the type byte)
+            stub.push(EXTERNAL_FLAG | self.compression.type_byte());
+            ChunkEncoding::External {
+                stub,
+                payload: compressed_data,
+            }
         }
+    }
+
+    // Length of the external payload for a chunk, if it overflowed into a
+    // `.mcc` file. Builds/reuses the cached layout for its owning region.
+    fn mcc_payload_len(&self, chunk_x: i32, chunk_z: i32) -> Option<usize> {
+        let region_x = chunk_x.div_euclid(32);
+        let region_z = chunk_z.div_euclid(32);
+        let layout = self.get_layout(region_x, region_z);
+        layout.external_payload(chunk_x, chunk_z).map(|p| p.len())
+    }
+
+    // The block name assigned to each section from Y=-4 to Y=19, in order.
+    // This fully determines a chunk's generated content today (everything
+    // else is a function of this), so it doubles as the cache signature.
+    fn section_block_names(chunk_x: i32, chunk_z: i32) -> Vec<String> {
+        let _ = (chunk_x, chunk_z); // generation is not yet coordinate-dependent
+        (-4..20)
+            .map(|section_y| {
+                if section_y == -4 {
+                    "minecraft:dirt"
+                } else {
+                    "minecraft:air"
+                }
+                .to_string()
+            })
+            .collect()
+    }
+
+    // Build the chunk NBT body (with placeholder x_pos/z_pos) for a given
+    // content signature. Coordinates are patched in afterwards by the cache.
+    fn build_chunk_template(section_blocks: &[String]) -> Vec<u8> {
+        let sections = section_blocks
+            .iter()
+            .enumerate()
+            .map(|(i, block_name)| {
+                let section_y = i as i32 - 4;
+                Section {
+                    y: section_y as i8,
+                    block_states: BlockStates {
+                        palette: vec![BlockState {
+                            name: block_name.clone(),
+                        }],
+                    },
+                    biomes: Biomes {
+                        // Biomes are mandatory in 1.21. We set everything to Plains.
+                        palette: vec!["minecraft:plains".to_string()],
+                    },
+                }
+            })
+            .collect();
 
-        // Assemble the Chunk
         let chunk = ChunkData {
             data_version: 3955, // 1.21.1 Version ID
-            x_pos: chunk_x,
-            z_pos: chunk_z,
-            y_pos: -4, // Lowest section Y (-4 * 16 = -64)
+            x_pos: 0,           // placeholder, patched in by the generation cache
+            z_pos: 0,           // placeholder, patched in by the generation cache
+            y_pos: -4,          // Lowest section Y (-4 * 16 = -64)
             status: "minecraft:full".to_string(),
             last_update: 0,
             inhabited_time: 0,
             is_light_on: 1, // Light has been calculated
+            heightmaps: build_heightmaps(section_blocks),
             sections,
         };
 
-        // 1. Serialize struct to NBT bytes
-        let nbt_data = fastnbt::to_bytes(&chunk).unwrap();
-
-        // 2. Compress using Zlib (required by Minecraft)
-        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(&nbt_data).unwrap();
-        let compressed_data = encoder.finish().unwrap();
+        fastnbt::to_bytes(&chunk).unwrap()
+    }
 
-        // 3. Wrap in MCA format: [Length (4 bytes)] + [CompressionType (1 byte)] + [Data]
-        let mut final_blob = Vec::new();
-        let total_len = (compressed_data.len() + 1) as u32; // +1 for the compression byte
-        final_blob.extend_from_slice(&total_len.to_be_bytes()); // Big Endian!
-        final_blob.push(2); // Type 2 = Zlib
-        final_blob.extend_from_slice(&compressed_data);
+    // Build the raw (uncompressed) chunk NBT bytes for the given coordinates,
+    // sharing a generated body across chunks with identical content.
+    fn generate_chunk_nbt(&self, chunk_x: i32, chunk_z: i32) -> Vec<u8> {
+        let section_blocks = Self::section_block_names(chunk_x, chunk_z);
+        let signature = ChunkSignature {
+            section_blocks: section_blocks.clone(),
+        };
 
-        final_blob
+        self.generation_cache.get_or_generate(signature, chunk_x, chunk_z, || {
+            Self::build_chunk_template(&section_blocks)
+        })
     }
 }
 
@@ -160,11 +880,19 @@ impl Filesystem for AnvilFS {
             };
             reply.attr(&TTL, &attr);
         } else {
-            // Any other Inode is considered a Region File
+            // Any other Inode is either a Region File or an overflow .mcc file
+            let entry = self.inode_map.read().unwrap().get(&ino).copied();
+            let size = match entry {
+                Some(InodeEntry::Mcc(chunk_x, chunk_z)) => {
+                    self.mcc_payload_len(chunk_x, chunk_z).unwrap_or(0) as u64
+                }
+                // Fake size: 10MB. It must be large enough so Java thinks it
+                // can seek inside.
+                _ => 10 * 1024 * 1024,
+            };
             let attr = FileAttr {
-                ino: ino,
-                size: 10 * 1024 * 1024, // Fake size: 10MB. 
-                                        // It must be large enough so Java thinks it can seek inside.
+                ino,
+                size,
                 blocks: 1,
                 atime: ts, mtime: ts, ctime: ts, crtime: ts,
                 kind: FileType::RegularFile, // It's a file
@@ -186,13 +914,12 @@ impl Filesystem for AnvilFS {
         }
 
         let filename = name.to_str().unwrap_or("");
+        let ts = UNIX_EPOCH;
 
-        // Parse region filename to get coordinates
         if let Some((region_x, region_z)) = Self::parse_region_name(filename) {
             // Get or create a unique inode for this region
-            let ino = self.get_or_create_inode(region_x, region_z);
+            let ino = self.get_or_create_inode(InodeEntry::Region(region_x, region_z));
 
-            let ts = UNIX_EPOCH;
             let attr = FileAttr {
                 ino,
                 size: 10 * 1024 * 1024,
@@ -203,8 +930,26 @@ impl Filesystem for AnvilFS {
                 nlink: 1, uid: 1000, gid: 1000, rdev: 0, flags: 0, blksize: 512,
             };
             reply.entry(&TTL, &attr, 0);
+        } else if let Some((chunk_x, chunk_z)) = Self::parse_mcc_name(filename) {
+            // Only expose a .mcc entry if the chunk actually overflowed its region sector.
+            match self.mcc_payload_len(chunk_x, chunk_z) {
+                Some(size) => {
+                    let ino = self.get_or_create_inode(InodeEntry::Mcc(chunk_x, chunk_z));
+                    let attr = FileAttr {
+                        ino,
+                        size: size as u64,
+                        blocks: 1,
+                        atime: ts, mtime: ts, ctime: ts, crtime: ts,
+                        kind: FileType::RegularFile,
+                        perm: 0o644,
+                        nlink: 1, uid: 1000, gid: 1000, rdev: 0, flags: 0, blksize: 512,
+                    };
+                    reply.entry(&TTL, &attr, 0);
+                }
+                None => reply.error(ENOENT),
+            }
         } else {
-            // Not a valid region file
+            // Not a valid region/overflow file
             reply.error(ENOENT);
         }
     }
@@ -230,11 +975,11 @@ impl Filesystem for AnvilFS {
     // 4. READ (Give me the bytes!)
     // Handles reads that may span multiple zones (header, timestamps, chunk data)
     fn read(&mut self, _req: &Request, ino: u64, _fh: u64, offset: i64, size: u32, _flags: i32, _lock: Option<u64>, reply: ReplyData) {
-        // Look up region coordinates from inode
-        let (region_x, region_z) = {
-            let map = self.inode_map.lock().unwrap();
+        // Look up what this inode refers to
+        let entry = {
+            let map = self.inode_map.read().unwrap();
             match map.get(&ino) {
-                Some(&coords) => coords,
+                Some(&entry) => entry,
                 None => {
                     reply.data(&vec![0u8; size as usize]);
                     return;
@@ -242,6 +987,28 @@ impl Filesystem for AnvilFS {
             }
         };
 
+        let (region_x, region_z) = match entry {
+            InodeEntry::Region(rx, rz) => (rx, rz),
+            InodeEntry::Mcc(chunk_x, chunk_z) => {
+                // Overflow file: serve the raw payload directly, no framing.
+                let region_x = chunk_x.div_euclid(32);
+                let region_z = chunk_z.div_euclid(32);
+                let layout = self.get_layout(region_x, region_z);
+                let result = match layout.external_payload(chunk_x, chunk_z) {
+                    Some(payload) => {
+                        let start = std::cmp::min(offset as usize, payload.len());
+                        let end = std::cmp::min(start + size as usize, payload.len());
+                        payload[start..end].to_vec()
+                    }
+                    None => Vec::new(),
+                };
+                reply.data(&result);
+                return;
+            }
+        };
+
+        let layout = self.get_layout(region_x, region_z);
+
         let offset = offset as usize;
         let size = size as usize;
         let mut result = vec![0u8; size];
@@ -253,19 +1020,26 @@ impl Filesystem for AnvilFS {
             let zone_end = std::cmp::min(offset + size, 4096);
             let bytes_to_copy = zone_end - zone_start;
 
-            // Generate location table
+            // Generate location table from the real per-chunk sector layout
             for i in 0..1024u32 {
-                let sector_offset = 2 + i;
+                let local_x = (i % 32) as i32;
+                let local_z = (i / 32) as i32;
+                let slot = layout.slot(local_x, local_z);
                 let entry_start = (i as usize) * 4;
 
                 // Only generate entries we need
                 if entry_start + 4 > zone_start && entry_start < zone_end {
-                    let bytes = [
-                        ((sector_offset >> 16) & 0xFF) as u8,
-                        ((sector_offset >> 8) & 0xFF) as u8,
-                        (sector_offset & 0xFF) as u8,
-                        1u8, // sector count
-                    ];
+                    let bytes = if slot.sector_count == 0 {
+                        // Empty chunk: offset 0 / count 0
+                        [0u8, 0u8, 0u8, 0u8]
+                    } else {
+                        [
+                            ((slot.sector_offset >> 16) & 0xFF) as u8,
+                            ((slot.sector_offset >> 8) & 0xFF) as u8,
+                            (slot.sector_offset & 0xFF) as u8,
+                            slot.sector_count,
+                        ]
+                    };
                     for (j, &byte) in bytes.iter().enumerate() {
                         let file_pos = entry_start + j;
                         if file_pos >= zone_start && file_pos < zone_end {
@@ -288,41 +1062,36 @@ impl Filesystem for AnvilFS {
 
         // --- ZONE C: Chunk Data (bytes 8192+) ---
         if offset + size > 8192 && pos < size {
-            let data_start = std::cmp::max(offset, 8192);
-            let data_end = offset + size;
+            let data_start = std::cmp::max(offset, 8192) as u64;
+            let data_end = (offset + size) as u64;
 
-            // Process each chunk that the read touches
-            let first_chunk = (data_start - 8192) / CHUNK_PADDING as usize;
-            let last_chunk = (data_end - 8192 - 1) / CHUNK_PADDING as usize;
+            for local_z in 0..32i32 {
+                for local_x in 0..32i32 {
+                    let slot = layout.slot(local_x, local_z);
+                    if slot.sector_count == 0 {
+                        continue;
+                    }
 
-            for chunk_idx in first_chunk..=last_chunk {
-                let chunk_file_start = 8192 + chunk_idx * CHUNK_PADDING as usize;
-                let chunk_file_end = chunk_file_start + CHUNK_PADDING as usize;
+                    let chunk_file_start = slot.sector_offset as u64 * CHUNK_PADDING;
+                    let chunk_file_end =
+                        chunk_file_start + slot.sector_count as u64 * CHUNK_PADDING;
 
-                // Calculate overlap between request and this chunk
-                let overlap_start = std::cmp::max(offset, chunk_file_start);
-                let overlap_end = std::cmp::min(offset + size, chunk_file_end);
+                    // Calculate overlap between request and this chunk's sectors
+                    let overlap_start = std::cmp::max(data_start, chunk_file_start);
+                    let overlap_end = std::cmp::min(data_end, chunk_file_end);
+                    if overlap_start >= overlap_end {
+                        continue;
+                    }
 
-                if overlap_start >= overlap_end {
-                    continue;
-                }
+                    let blob_start = (overlap_start - chunk_file_start) as usize;
+                    let blob_end = (overlap_end - chunk_file_start) as usize;
+                    let result_start = (overlap_start - offset as u64) as usize;
 
-                // Generate chunk data
-                let local_z = (chunk_idx / 32) as i32;
-                let local_x = (chunk_idx % 32) as i32;
-                let world_chunk_x = region_x * 32 + local_x;
-                let world_chunk_z = region_z * 32 + local_z;
-                let blob = self.generate_chunk_bytes(world_chunk_x, world_chunk_z);
-
-                // Copy relevant portion
-                let blob_start = overlap_start - chunk_file_start;
-                let blob_end = overlap_end - chunk_file_start;
-                let result_start = overlap_start - offset;
-
-                for i in blob_start..blob_end {
-                    let result_idx = result_start + (i - blob_start);
-                    if result_idx < size {
-                        result[result_idx] = if i < blob.len() { blob[i] } else { 0 };
+                    for i in blob_start..blob_end {
+                        let result_idx = result_start + (i - blob_start);
+                        if result_idx < size {
+                            result[result_idx] = if i < slot.blob.len() { slot.blob[i] } else { 0 };
+                        }
                     }
                 }
             }
@@ -338,22 +1107,33 @@ impl Filesystem for AnvilFS {
         reply.opened(0, 0);
     }
 
-    // 6. WRITE (Accept writes but discard them)
-    // For MVP: we pretend to accept writes but don't store anything.
-    // This allows Minecraft to "save" without errors.
+    // 6. WRITE (Persist to the backing store, if any; otherwise discard)
     fn write(
         &mut self,
         _req: &Request,
-        _ino: u64,
+        ino: u64,
         _fh: u64,
-        _offset: i64,
+        offset: i64,
         data: &[u8],
         _write_flags: u32,
         _flags: i32,
         _lock_owner: Option<u64>,
         reply: fuser::ReplyWrite,
     ) {
-        // Pretend we wrote all the bytes
+        if let Some(dir) = &self.backing_dir {
+            let entry = self.inode_map.read().unwrap().get(&ino).copied();
+            if let Some(InodeEntry::Region(region_x, region_z)) = entry {
+                let path = dir.join(format!("r.{}.{}.mca", region_x, region_z));
+                if write_backing_bytes(&path, offset as u64, data).is_ok() {
+                    // The cached layout is now stale; drop it so the next
+                    // read rebuilds from the file we just wrote.
+                    self.region_layouts.write().unwrap().remove(&(region_x, region_z));
+                }
+            }
+        }
+
+        // Always report success: Minecraft must see its save complete even
+        // when there is no backing store to persist into.
         reply.written(data.len() as u32);
     }
 
@@ -377,7 +1157,16 @@ impl Filesystem for AnvilFS {
     }
 
     // 9. FSYNC (Force sync to disk)
-    fn fsync(&mut self, _req: &Request, _ino: u64, _fh: u64, _datasync: bool, reply: fuser::ReplyEmpty) {
+    fn fsync(&mut self, _req: &Request, ino: u64, _fh: u64, _datasync: bool, reply: fuser::ReplyEmpty) {
+        if let Some(dir) = &self.backing_dir {
+            let entry = self.inode_map.read().unwrap().get(&ino).copied();
+            if let Some(InodeEntry::Region(region_x, region_z)) = entry {
+                let path = dir.join(format!("r.{}.{}.mca", region_x, region_z));
+                if let Ok(file) = std::fs::File::open(&path) {
+                    let _ = file.sync_all();
+                }
+            }
+        }
         reply.ok();
     }
 }
@@ -397,8 +1186,152 @@ fn main() {
         MountOption::AllowOther,  // REQUIRED for Docker to share the mount
     ];
 
+    let print_stats = std::env::args().any(|arg| arg == "--stats");
+    let backing_dir = std::env::var("ANVILFS_BACKING_DIR").ok().map(PathBuf::from);
+
+    // Offline maintenance modes: repair and/or compact every region file in
+    // the backing directory, then exit without mounting anything.
+    let run_repair = std::env::args().any(|arg| arg == "--repair");
+    let run_compact = std::env::args().any(|arg| arg == "--compact");
+    if run_repair || run_compact {
+        let dir = backing_dir
+            .as_ref()
+            .expect("--repair/--compact require ANVILFS_BACKING_DIR to be set");
+        for entry in std::fs::read_dir(dir).expect("backing dir must be readable") {
+            let path = entry.expect("readable dir entry").path();
+            let name = match path.file_name().and_then(|n| n.to_str()) {
+                Some(name) if AnvilFS::parse_region_name(name).is_some() => name,
+                _ => continue,
+            };
+
+            if run_repair {
+                match repair_region_file(&path) {
+                    Ok(0) => {}
+                    Ok(n) => println!("repaired {} corrupted chunk(s) in {}", n, name),
+                    Err(e) => eprintln!("failed to repair {}: {}", name, e),
+                }
+            }
+            if run_compact {
+                if let Err(e) = compact_region_file(&path) {
+                    eprintln!("failed to compact {}: {}", name, e);
+                }
+            }
+        }
+        return;
+    }
+
     println!("Starting FUSE mount at {}...", mountpoint);
 
+    let fs = AnvilFS::new(backing_dir);
+
+    if print_stats {
+        let cache = fs.generation_cache.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(Duration::from_secs(10));
+            println!("{}", cache.stats_line());
+        });
+    }
+
     // 4. Start the loop. This blocks forever until the program is killed.
-    fuser::mount2(AnvilFS::new(), mountpoint, &options).unwrap();
+    fuser::mount2(fs, mountpoint, &options).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    // Just enough of the real schema to read back what `find_int_tag_offset`
+    // patched — `nbt_structs::ChunkData` only derives `Serialize`, so this
+    // stands in for it; `storage::region_file` verifies coordinates through
+    // the separate, `Deserialize`-able `nbt::ChunkData` instead.
+    #[derive(Deserialize)]
+    struct ChunkCoords {
+        #[serde(rename = "xPos")]
+        x_pos: i32,
+        #[serde(rename = "zPos")]
+        z_pos: i32,
+    }
+
+    fn sample_chunk_nbt(x_pos: i32, z_pos: i32) -> Vec<u8> {
+        let chunk = ChunkData {
+            data_version: 4671,
+            x_pos,
+            z_pos,
+            y_pos: MIN_WORLD_Y / 16,
+            status: "minecraft:full".to_string(),
+            last_update: 0,
+            inhabited_time: 0,
+            is_light_on: 0,
+            sections: Vec::new(),
+            heightmaps: Heightmaps {
+                motion_blocking: fastnbt::LongArray::new(Vec::new()),
+                world_surface: fastnbt::LongArray::new(Vec::new()),
+            },
+        };
+        fastnbt::to_bytes(&chunk).unwrap()
+    }
+
+    #[test]
+    fn test_find_int_tag_offset_locates_patchable_coordinates() {
+        let template = sample_chunk_nbt(11, -22);
+        let x_pos_offset = find_int_tag_offset(&template, "xPos").unwrap();
+        let z_pos_offset = find_int_tag_offset(&template, "zPos").unwrap();
+
+        assert_eq!(
+            i32::from_be_bytes(template[x_pos_offset..x_pos_offset + 4].try_into().unwrap()),
+            11
+        );
+        assert_eq!(
+            i32::from_be_bytes(template[z_pos_offset..z_pos_offset + 4].try_into().unwrap()),
+            -22
+        );
+    }
+
+    #[test]
+    fn test_patched_coordinates_still_deserialize_correctly() {
+        // Built with placeholder coordinates, then patched the same way
+        // `GenerationCache::get_or_generate` patches a cache hit — this is
+        // the round trip the review flagged as untested: a byte-patch this
+        // fragile needs to prove the result still deserializes to the real
+        // coordinates, not just that the offsets were found.
+        let mut patched = sample_chunk_nbt(0, 0);
+        let x_pos_offset = find_int_tag_offset(&patched, "xPos").unwrap();
+        let z_pos_offset = find_int_tag_offset(&patched, "zPos").unwrap();
+        patched[x_pos_offset..x_pos_offset + 4].copy_from_slice(&123i32.to_be_bytes());
+        patched[z_pos_offset..z_pos_offset + 4].copy_from_slice(&(-456i32).to_be_bytes());
+
+        let coords: ChunkCoords = fastnbt::from_bytes(&patched).unwrap();
+        assert_eq!(coords.x_pos, 123);
+        assert_eq!(coords.z_pos, -456);
+    }
+
+    #[test]
+    fn test_generation_cache_hit_patches_distinct_coordinates() {
+        let cache = GenerationCache::new();
+        let signature = ChunkSignature {
+            section_blocks: vec!["minecraft:stone".to_string()],
+        };
+
+        let mut calls = 0;
+        let first = cache.get_or_generate(signature.clone(), 1, 2, || {
+            calls += 1;
+            sample_chunk_nbt(0, 0)
+        });
+        let second = cache.get_or_generate(signature, 3, -4, || {
+            calls += 1;
+            sample_chunk_nbt(0, 0)
+        });
+
+        // The second call is a cache hit, so `generate` only ran once, and
+        // the cached template is what got patched for both calls.
+        assert_eq!(calls, 1);
+        assert_eq!(cache.stats_line(), "generation cache: 1 hits, 1 misses, 1 unique blobs");
+
+        let first_coords: ChunkCoords = fastnbt::from_bytes(&first).unwrap();
+        assert_eq!((first_coords.x_pos, first_coords.z_pos), (1, 2));
+
+        let second_coords: ChunkCoords = fastnbt::from_bytes(&second).unwrap();
+        assert_eq!((second_coords.x_pos, second_coords.z_pos), (3, -4));
+    }
 }
\ No newline at end of file
@@ -3,6 +3,8 @@
 //! These structures are serialized using fastnbt to create valid
 //! Minecraft chunk data compatible with version 1.21.11.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Minecraft data version for 1.21.11 (default).
@@ -63,6 +65,76 @@ pub struct Section {
     // The biomes inside this section
     #[serde(rename = "biomes", alias = "Biomes", default)]
     pub biomes: Option<Biomes>,
+
+    /// Packed block-light nibble array (2048 bytes, one nibble per block,
+    /// low nibble first within each byte). `None` until a lighting pass
+    /// has run over the section.
+    #[serde(rename = "BlockLight", default)]
+    pub block_light: Option<fastnbt::ByteArray>,
+
+    /// Packed sky-light nibble array, same encoding as `block_light`.
+    #[serde(rename = "SkyLight", default)]
+    pub sky_light: Option<fastnbt::ByteArray>,
+}
+
+/// One light level per block in a section (16x16x16).
+pub const LIGHT_LEVEL_COUNT: usize = 4096;
+
+// Section-local light index ordering, matching vanilla's BlockLight/
+// SkyLight layout: `((y * 16) + z) * 16 + x`.
+fn light_index(x: u8, y: u8, z: u8) -> usize {
+    (y as usize * 16 + z as usize) * 16 + x as usize
+}
+
+/// Packs one nibble (0..=15, low bits kept) per block, in [`light_index`]
+/// order, into a 2048-byte nibble array — low nibble first within each
+/// byte, matching vanilla's `BlockLight`/`SkyLight` encoding.
+pub fn pack_light(levels: &[u8; LIGHT_LEVEL_COUNT]) -> fastnbt::ByteArray {
+    let mut bytes = [0u8; LIGHT_LEVEL_COUNT / 2];
+    for (i, &level) in levels.iter().enumerate() {
+        let nibble = level & 0x0F;
+        if i % 2 == 0 {
+            bytes[i / 2] |= nibble;
+        } else {
+            bytes[i / 2] |= nibble << 4;
+        }
+    }
+    fastnbt::ByteArray::new(bytes.iter().map(|&b| b as i8).collect())
+}
+
+/// Inverse of [`pack_light`].
+pub fn unpack_light(data: &fastnbt::ByteArray) -> [u8; LIGHT_LEVEL_COUNT] {
+    let mut levels = [0u8; LIGHT_LEVEL_COUNT];
+    for (i, level) in levels.iter_mut().enumerate() {
+        let byte = data[i / 2] as u8;
+        *level = if i % 2 == 0 { byte & 0x0F } else { (byte >> 4) & 0x0F };
+    }
+    levels
+}
+
+/// Reads the light level at chunk-local `(x, y, z)` within a section from
+/// its packed nibble array. `None` (no lighting pass yet) reads as 0.
+pub fn get_light(data: Option<&fastnbt::ByteArray>, x: u8, y: u8, z: u8) -> u8 {
+    match data {
+        Some(data) => {
+            let i = light_index(x, y, z);
+            let byte = data[i / 2] as u8;
+            if i % 2 == 0 { byte & 0x0F } else { (byte >> 4) & 0x0F }
+        }
+        None => 0,
+    }
+}
+
+/// Sets the light level at chunk-local `(x, y, z)` within a section's
+/// packed nibble array, allocating one (all-zero elsewhere) if `data` is
+/// `None`.
+pub fn set_light(data: &mut Option<fastnbt::ByteArray>, x: u8, y: u8, z: u8, level: u8) {
+    let mut levels = match data.as_ref() {
+        Some(existing) => unpack_light(existing),
+        None => [0u8; LIGHT_LEVEL_COUNT],
+    };
+    levels[light_index(x, y, z)] = level & 0x0F;
+    *data = Some(pack_light(&levels));
 }
 
 // --- Block Palette ---
@@ -77,6 +149,36 @@ pub struct BlockStates {
     pub data: Option<fastnbt::LongArray>,
 }
 
+/// One index per block in a section (16x16x16).
+pub const BLOCK_INDEX_COUNT: usize = 4096;
+/// One index per biome sample in a section (4x4x4).
+pub const BIOME_INDEX_COUNT: usize = 64;
+
+impl BlockStates {
+    /// Packs `indices` (one per block, [`BLOCK_INDEX_COUNT`] of them) into
+    /// the 1.16+ paletted long-array encoding for a palette of
+    /// `palette_len` entries. `None` means "omit `data`", which is what
+    /// vanilla does whenever there's only one possible index.
+    pub fn pack(indices: &[u16], palette_len: usize) -> Option<fastnbt::LongArray> {
+        if palette_len <= 1 {
+            return None;
+        }
+        Some(fastnbt::LongArray::new(pack_indices(
+            indices,
+            bits_for_palette(palette_len, 4),
+        )))
+    }
+
+    /// Inverse of [`Self::pack`]. `data == None` means every one of the
+    /// `len` blocks uses palette index 0 (the single-entry case).
+    pub fn unpack(data: Option<&fastnbt::LongArray>, palette_len: usize, len: usize) -> Vec<u16> {
+        match data {
+            Some(data) => unpack_indices(data, bits_for_palette(palette_len, 4), len),
+            None => vec![0; len],
+        }
+    }
+}
+
 // --- Biome Palette ---
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Biomes {
@@ -86,10 +188,155 @@ pub struct Biomes {
     pub data: Option<fastnbt::LongArray>,
 }
 
+impl Biomes {
+    /// Same encoding as [`BlockStates::pack`], but with a 1-bit floor
+    /// instead of 4 (biome palettes are usually much smaller).
+    pub fn pack(indices: &[u16], palette_len: usize) -> Option<fastnbt::LongArray> {
+        if palette_len <= 1 {
+            return None;
+        }
+        Some(fastnbt::LongArray::new(pack_indices(
+            indices,
+            bits_for_palette(palette_len, 1),
+        )))
+    }
+
+    /// Inverse of [`Self::pack`].
+    pub fn unpack(data: Option<&fastnbt::LongArray>, palette_len: usize, len: usize) -> Vec<u16> {
+        match data {
+            Some(data) => unpack_indices(data, bits_for_palette(palette_len, 1), len),
+            None => vec![0; len],
+        }
+    }
+}
+
+// Bits per palette index: at least `min_bits`, otherwise
+// `ceil(log2(palette_len))` so every index fits.
+fn bits_for_palette(palette_len: usize, min_bits: u32) -> u32 {
+    let needed = if palette_len <= 1 {
+        0
+    } else {
+        32 - ((palette_len - 1) as u32).leading_zeros()
+    };
+    needed.max(min_bits)
+}
+
+// Packs `indices` into `i64`s, `bits` wide each, `entries_per_long = 64 /
+// bits` per long, with no index allowed to straddle a long boundary.
+fn pack_indices(indices: &[u16], bits: u32) -> Vec<i64> {
+    let entries_per_long = (64 / bits) as usize;
+    let mask: i64 = (1i64 << bits) - 1;
+    let longs_needed = (indices.len() + entries_per_long - 1) / entries_per_long;
+    let mut longs = vec![0i64; longs_needed.max(1)];
+
+    for (i, &index) in indices.iter().enumerate() {
+        let long_index = i / entries_per_long;
+        let slot = i % entries_per_long;
+        longs[long_index] |= ((index as i64) & mask) << (slot as u32 * bits);
+    }
+
+    longs
+}
+
+// Inverse of `pack_indices`.
+fn unpack_indices(data: &fastnbt::LongArray, bits: u32, len: usize) -> Vec<u16> {
+    let entries_per_long = (64 / bits) as usize;
+    let mask: i64 = (1i64 << bits) - 1;
+    let mut indices = Vec::with_capacity(len);
+
+    for i in 0..len {
+        let long_index = i / entries_per_long;
+        let slot = i % entries_per_long;
+        let value = (data[long_index] >> (slot as u32 * bits)) & mask;
+        indices.push(value as u16);
+    }
+
+    indices
+}
+
 // --- Single Block ---
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockState {
     #[serde(rename = "Name")]
     pub name: String,
-    // Properties (like waterlogged, facing) are optional/omitted for MVP.
+    // Variant properties, e.g. waterlogged/facing/half/axis. Omitted
+    // entirely (rather than serialized as an empty compound) when a block
+    // has none, matching vanilla's NBT.
+    #[serde(rename = "Properties", default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<BTreeMap<String, String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(indices: &[u16], palette_len: usize) {
+        let packed = BlockStates::pack(indices, palette_len);
+        let unpacked = BlockStates::unpack(packed.as_ref(), palette_len, indices.len());
+        assert_eq!(unpacked, indices, "palette_len={palette_len}");
+    }
+
+    #[test]
+    fn test_single_entry_palette_omits_data() {
+        // palette_len <= 1 means every index is implicitly 0 and `pack`
+        // stores nothing at all.
+        assert!(BlockStates::pack(&[0; BLOCK_INDEX_COUNT], 1).is_none());
+        let unpacked = BlockStates::unpack(None, 1, BLOCK_INDEX_COUNT);
+        assert_eq!(unpacked, vec![0; BLOCK_INDEX_COUNT]);
+    }
+
+    #[test]
+    fn test_roundtrip_four_bit_floor() {
+        // palette_len = 2 would need only 1 bit, but BlockStates enforces a
+        // 4-bit floor.
+        let indices: Vec<u16> = (0..BLOCK_INDEX_COUNT).map(|i| (i % 2) as u16).collect();
+        roundtrip(&indices, 2);
+    }
+
+    #[test]
+    fn test_roundtrip_straddling_bit_widths() {
+        // 5 and 6 bits don't divide 64 evenly (64/5 = 12 entries with a
+        // remainder, 64/6 = 10 with a remainder), so the last used slot in
+        // each long leaves unused high bits rather than an index straddling
+        // two longs — exactly the case `pack_indices`/`unpack_indices` are
+        // supposed to avoid getting wrong.
+        for palette_len in [17usize, 33, 63] {
+            let bits = 32 - ((palette_len - 1) as u32).leading_zeros();
+            assert!(bits == 5 || bits == 6, "test assumes a 5 or 6 bit index, got {bits}");
+
+            let indices: Vec<u16> =
+                (0..BLOCK_INDEX_COUNT).map(|i| (i % palette_len) as u16).collect();
+            roundtrip(&indices, palette_len);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_non_multiple_of_entries_per_long() {
+        // BLOCK_INDEX_COUNT (4096) isn't always a multiple of
+        // `entries_per_long`, so the final long in `pack_indices`'s output
+        // is only partially filled; make sure trailing garbage in those
+        // unused high bits doesn't leak into `unpack_indices`.
+        let palette_len = 17; // 5 bits/index, 12 entries per long, 4096 % 12 != 0
+        let indices: Vec<u16> =
+            (0..BLOCK_INDEX_COUNT).map(|i| ((i * 7) % palette_len) as u16).collect();
+        roundtrip(&indices, palette_len);
+    }
+
+    #[test]
+    fn test_roundtrip_max_palette_len() {
+        // Largest realistic block palette size for a single section.
+        let palette_len = BLOCK_INDEX_COUNT;
+        let indices: Vec<u16> =
+            (0..BLOCK_INDEX_COUNT).map(|i| (i % palette_len) as u16).collect();
+        roundtrip(&indices, palette_len);
+    }
+
+    #[test]
+    fn test_biomes_roundtrip_one_bit_floor() {
+        // Biomes use a 1-bit floor instead of BlockStates' 4-bit floor.
+        let indices: Vec<u16> = (0..BIOME_INDEX_COUNT).map(|i| (i % 2) as u16).collect();
+        let packed = Biomes::pack(&indices, 2);
+        let unpacked = Biomes::unpack(packed.as_ref(), 2, indices.len());
+        assert_eq!(unpacked, indices);
+    }
 }
\ No newline at end of file
@@ -35,6 +35,23 @@ pub struct ChunkData {
 
     // Vertical slices of the chunk (16 blocks high each)
     pub sections: Vec<Section>,
+
+    // Surface heightmaps. Required: without them the client re-derives
+    // lighting/mob-spawn surfaces itself and forces a relight on load.
+    #[serde(rename = "Heightmaps")]
+    pub heightmaps: Heightmaps,
+}
+
+// --- Heightmaps ---
+// Each array is one bit-packed `i64` long-array entry per (x, z) column
+// (256 columns, indexed `x + z*16`), `ceil(log2(world_height))` bits wide,
+// with entries never allowed to straddle a long boundary.
+#[derive(Debug, Serialize)]
+pub struct Heightmaps {
+    #[serde(rename = "MOTION_BLOCKING")]
+    pub motion_blocking: fastnbt::LongArray,
+    #[serde(rename = "WORLD_SURFACE")]
+    pub world_surface: fastnbt::LongArray,
 }
 
 // --- Section (16x16x16 Cube) ---
@@ -4,7 +4,8 @@
 //! - Location table: where each chunk is stored
 //! - Timestamp table: when each chunk was last saved
 
-use super::{SECTOR_SIZE, HEADER_SIZE};
+use super::HEADER_BYTES;
+use super::layout::LocationEntry;
 
 /// MCA file header generator.
 ///
@@ -13,36 +14,37 @@ use super::{SECTOR_SIZE, HEADER_SIZE};
 pub struct Header;
 
 impl Header {
-    /// Generate complete header (8192 bytes).
-    pub fn generate() -> Vec<u8> {
-        let mut header = vec![0u8; HEADER_SIZE];
+    /// Generate complete header (8192 bytes) from a region's packed
+    /// location table (see [`super::RegionLayout::locations`]) and its
+    /// per-chunk last-write-time table (Unix seconds, big-endian; `0` for a
+    /// chunk that's never been written).
+    pub fn generate(locations: &[LocationEntry], timestamps: &[u32]) -> Vec<u8> {
+        let mut header = vec![0u8; HEADER_BYTES as usize];
 
         // Location table (first 4096 bytes)
         // Each entry: 3 bytes offset + 1 byte sector count
-        for i in 0..1024u32 {
-            // Each chunk starts at sector (2 + i)
-            // Sector 0-1 are the header itself
-            let sector_offset = 2 + i;
-            let sector_count: u8 = 1;
-
-            let entry_offset = (i as usize) * 4;
+        for (i, &(sector_offset, sector_count)) in locations.iter().enumerate() {
+            let entry_offset = i * 4;
             header[entry_offset] = ((sector_offset >> 16) & 0xFF) as u8;
             header[entry_offset + 1] = ((sector_offset >> 8) & 0xFF) as u8;
             header[entry_offset + 2] = (sector_offset & 0xFF) as u8;
             header[entry_offset + 3] = sector_count;
         }
 
-        // Timestamp table (second 4096 bytes) - all zeros
-        // Already initialized to 0
+        // Timestamp table (second 4096 bytes): one big-endian u32 per chunk
+        for (i, &timestamp) in timestamps.iter().enumerate() {
+            let entry_offset = 4096 + i * 4;
+            header[entry_offset..entry_offset + 4].copy_from_slice(&timestamp.to_be_bytes());
+        }
 
         header
     }
 
     /// Get a slice of the header for a specific byte range.
-    pub fn get_range(offset: usize, size: usize) -> Vec<u8> {
-        let header = Self::generate();
-        let end = std::cmp::min(offset + size, HEADER_SIZE);
-        if offset >= HEADER_SIZE {
+    pub fn get_range(locations: &[LocationEntry], timestamps: &[u32], offset: usize, size: usize) -> Vec<u8> {
+        let header = Self::generate(locations, timestamps);
+        let end = std::cmp::min(offset + size, HEADER_BYTES as usize);
+        if offset >= HEADER_BYTES as usize {
             vec![0u8; size]
         } else {
             let mut result = header[offset..end].to_vec();
@@ -53,33 +55,32 @@ impl Header {
             result
         }
     }
-
-    /// Calculate sector offset for a chunk index.
-    #[inline]
-    pub fn chunk_sector(chunk_index: usize) -> u32 {
-        2 + chunk_index as u32
-    }
-
-    /// Calculate file offset for a chunk index.
-    #[inline]
-    pub fn chunk_offset(chunk_index: usize) -> usize {
-        Self::chunk_sector(chunk_index) as usize * SECTOR_SIZE
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn sample_locations() -> Vec<LocationEntry> {
+        let mut locations = vec![(0u32, 0u8); 1024];
+        locations[0] = (2, 1);
+        locations[1] = (3, 5);
+        locations
+    }
+
+    fn zero_timestamps() -> Vec<u32> {
+        vec![0u32; 1024]
+    }
+
     #[test]
     fn test_header_size() {
-        let header = Header::generate();
+        let header = Header::generate(&sample_locations(), &zero_timestamps());
         assert_eq!(header.len(), 8192);
     }
 
     #[test]
     fn test_first_chunk_location() {
-        let header = Header::generate();
+        let header = Header::generate(&sample_locations(), &zero_timestamps());
         // First chunk (index 0) should be at sector 2
         assert_eq!(header[0], 0); // high byte
         assert_eq!(header[1], 0); // mid byte
@@ -88,10 +89,28 @@ mod tests {
     }
 
     #[test]
-    fn test_chunk_offset() {
-        // Chunk 0 at sector 2 = byte 8192
-        assert_eq!(Header::chunk_offset(0), 8192);
-        // Chunk 1 at sector 3 = byte 12288
-        assert_eq!(Header::chunk_offset(1), 12288);
+    fn test_variable_sector_count() {
+        let header = Header::generate(&sample_locations(), &zero_timestamps());
+        // Second chunk (index 1) packs right after the first: sector 3,
+        // and its count reflects its actual compressed size (5 sectors).
+        let entry_offset = 4;
+        assert_eq!(header[entry_offset + 2], 3);
+        assert_eq!(header[entry_offset + 3], 5);
+    }
+
+    #[test]
+    fn test_unallocated_chunk_is_zero() {
+        let header = Header::generate(&sample_locations(), &zero_timestamps());
+        let entry_offset = 2 * 4;
+        assert_eq!(&header[entry_offset..entry_offset + 4], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_timestamp_table() {
+        let mut timestamps = zero_timestamps();
+        timestamps[1] = 0x0102_0304;
+        let header = Header::generate(&sample_locations(), &timestamps);
+        let entry_offset = 4096 + 1 * 4;
+        assert_eq!(&header[entry_offset..entry_offset + 4], &[0x01, 0x02, 0x03, 0x04]);
     }
 }
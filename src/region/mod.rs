@@ -1,9 +1,19 @@
 // Sparse Files for Emulationg Real files (so minecraft will see weight of file)
 
-pub const SECTOR_BYTES: u64 = 4096; // minecraft uses 4096 bytes per sector     
-pub const HEADER_BYTES: u64 = 8192; // header is 8192 bytes (2 sectors 8kb) 
+mod header;
+pub use header::Header;
 
+mod layout;
+pub use layout::{compress_chunk, rel_coords, CompressionType, LocationEntry, RegionLayout};
 
+pub const SECTOR_BYTES: u64 = 4096; // minecraft uses 4096 bytes per sector
+pub const HEADER_BYTES: u64 = 8192; // header is 8192 bytes (2 sectors 8kb)
+
+
+// Legacy fixed-stride layout (`SECTORS_PER_CHUNK` reserved for every chunk,
+// whether it needs that much space or not). Superseded by `RegionLayout`'s
+// tightly-packed allocator, but kept around as the simple reference
+// implementation the round-trip tests below exercise.
 pub const SECTORS_PER_CHUNK: u64 = 64; // 256kb per chunk
 
 
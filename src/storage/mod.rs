@@ -1,5 +1,14 @@
 use anyhow::Result;
 
+mod region_file;
+pub use region_file::{ChunkIssue, RegionFileStorage};
+
+mod scan;
+pub use scan::{scan_region, ChunkReport, RepairAction, RepairPolicy};
+
+mod encrypted;
+pub use encrypted::EncryptedStorage;
+
 pub trait ChunkStorage {
     fn read_chunk(&self, chunk_x: i32, chunk_z: i32) -> Result<Option<Vec<u8>>>;
     fn write_chunk(&self, chunk_x: i32, chunk_z: i32, data: &[u8]) -> Result<()>;
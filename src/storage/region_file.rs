@@ -0,0 +1,397 @@
+//! Real Anvil (`.mca`) region-file backend for `ChunkStorage`.
+//!
+//! Unlike the fixed-slot schemes used elsewhere in this codebase, this
+//! follows the genuine on-disk format: an 8 KiB header (a 1024-entry
+//! location table followed by a 1024-entry timestamp table) followed by
+//! chunk payloads packed into as few 4096-byte sectors as they actually
+//! need. Files written here are readable by vanilla Minecraft and other
+//! Anvil tools. `read_chunk`/`write_chunk` deal in raw (decompressed) NBT
+//! bytes; compression and on-disk framing are this backend's concern.
+//!
+//! DECISION: kept as its own backend, not merged into `main.rs`'s
+//! `RegionLayout` or `region::layout::RegionLayout`. Those two compute a
+//! region's *entire* sector layout once (procedurally or from a read-only
+//! backing directory) and never rewrite it in place; this type is a mutable
+//! `ChunkStorage` impl that reads and rewrites individual chunks directly
+//! against a real `.mca`/`.mcc` pair on disk — `find_free_run`/
+//! `occupied_sectors` exist specifically to reuse freed sectors across
+//! repeated single-chunk writes, which the other two never need to do.
+//! Sharing sector-offset arithmetic as a small helper (rather than one of
+//! the three owning the others' state) would be reasonable follow-up work,
+//! but isn't a prerequisite for this to be useful as-is.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use anyhow::{anyhow, Result};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::nbt::ChunkData;
+use super::ChunkStorage;
+
+/// Why [`RegionFileStorage::diagnose_chunk`] flagged a chunk as corrupt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChunkIssue {
+    /// The frame's declared length doesn't fit in the sectors the
+    /// location table allocated for it.
+    LengthOverflow,
+    /// The compression byte isn't gzip (1), zlib (2), or uncompressed (3).
+    UnknownCompression,
+    /// The compressed stream didn't decompress (or the external `.mcc`
+    /// file referenced by the external flag is missing/unreadable).
+    DecompressFailed,
+    /// The decompressed NBT didn't deserialize into [`ChunkData`].
+    DeserializeFailed,
+    /// The chunk's stored `xPos`/`zPos` don't match its slot.
+    CoordinateMismatch,
+}
+
+const SECTOR_SIZE: u64 = 4096;
+const HEADER_SECTORS: u64 = 2;
+const LOCATION_TABLE_SIZE: u64 = 4096;
+// A chunk whose payload needs more than this many sectors can't fit the
+// 1-byte sector count in the location table and spills into a sibling
+// `c.X.Z.mcc` file instead (real Anvil limit).
+const MAX_INLINE_SECTORS: usize = 255;
+const EXTERNAL_FLAG: u8 = 0x80;
+
+pub struct RegionFileStorage {
+    dir: PathBuf,
+    // Serializes header/sector-allocation mutations across writes. Reads
+    // take the shared side so they aren't blocked by each other.
+    lock: RwLock<()>,
+}
+
+impl RegionFileStorage {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        RegionFileStorage {
+            dir: dir.into(),
+            lock: RwLock::new(()),
+        }
+    }
+
+    fn region_path(&self, chunk_x: i32, chunk_z: i32) -> PathBuf {
+        let region_x = chunk_x.div_euclid(32);
+        let region_z = chunk_z.div_euclid(32);
+        self.dir.join(format!("r.{}.{}.mca", region_x, region_z))
+    }
+
+    fn mcc_path(&self, chunk_x: i32, chunk_z: i32) -> PathBuf {
+        self.dir.join(format!("c.{}.{}.mcc", chunk_x, chunk_z))
+    }
+
+    fn chunk_index(chunk_x: i32, chunk_z: i32) -> u64 {
+        ((chunk_x & 31) + (chunk_z & 31) * 32) as u64
+    }
+
+    fn read_location(file: &mut File, index: u64) -> Result<(u32, u8)> {
+        let mut entry = [0u8; 4];
+        file.seek(SeekFrom::Start(index * 4))?;
+        file.read_exact(&mut entry)?;
+        let offset = ((entry[0] as u32) << 16) | ((entry[1] as u32) << 8) | entry[2] as u32;
+        Ok((offset, entry[3]))
+    }
+
+    fn write_location(file: &mut File, index: u64, sector_offset: u32, sector_count: u8) -> Result<()> {
+        let bytes = [
+            ((sector_offset >> 16) & 0xFF) as u8,
+            ((sector_offset >> 8) & 0xFF) as u8,
+            (sector_offset & 0xFF) as u8,
+            sector_count,
+        ];
+        file.seek(SeekFrom::Start(index * 4))?;
+        file.write_all(&bytes)?;
+        Ok(())
+    }
+
+    fn write_timestamp(file: &mut File, index: u64, timestamp: u32) -> Result<()> {
+        file.seek(SeekFrom::Start(LOCATION_TABLE_SIZE + index * 4))?;
+        file.write_all(&timestamp.to_be_bytes())?;
+        Ok(())
+    }
+
+    // One bool per sector (by index, 0 = header) saying whether the
+    // location table currently claims it, built by scanning every entry.
+    fn occupied_sectors(file: &mut File) -> Result<Vec<bool>> {
+        let len = file.metadata()?.len();
+        let total_sectors = ((len + SECTOR_SIZE - 1) / SECTOR_SIZE).max(HEADER_SECTORS) as usize;
+        let mut occupied = vec![false; total_sectors];
+        for slot in occupied.iter_mut().take(HEADER_SECTORS as usize) {
+            *slot = true;
+        }
+
+        for index in 0..1024u64 {
+            let (sector_offset, sector_count) = Self::read_location(file, index)?;
+            if sector_offset == 0 || sector_count == 0 {
+                continue;
+            }
+            let end = sector_offset as usize + sector_count as usize;
+            if end > occupied.len() {
+                occupied.resize(end, false);
+            }
+            for slot in &mut occupied[sector_offset as usize..end] {
+                *slot = true;
+            }
+        }
+
+        Ok(occupied)
+    }
+
+    // First free run of `needed` contiguous sectors, after freeing the
+    // chunk's own current sectors (if any) since this write replaces them.
+    // Returns an offset past the end of `occupied` if no existing gap fits,
+    // meaning the caller should grow the file instead.
+    fn find_free_run(occupied: &mut [bool], own_offset: u32, own_count: u8, needed: usize) -> usize {
+        let own_end = own_offset as usize + own_count as usize;
+        let clamped_end = own_end.min(occupied.len());
+        for slot in &mut occupied[own_offset as usize..clamped_end] {
+            *slot = false;
+        }
+
+        let mut run_start = 0usize;
+        let mut run_len = 0usize;
+        for (i, &used) in occupied.iter().enumerate().skip(HEADER_SECTORS as usize) {
+            if used {
+                run_len = 0;
+                continue;
+            }
+            if run_len == 0 {
+                run_start = i;
+            }
+            run_len += 1;
+            if run_len == needed {
+                return run_start;
+            }
+        }
+
+        occupied.len()
+    }
+
+    /// All 1024 chunk-local coordinates belonging to region `(region_x,
+    /// region_z)`, in location-table slot order.
+    pub fn region_chunk_coords(region_x: i32, region_z: i32) -> Vec<(i32, i32)> {
+        let mut coords = Vec::with_capacity(1024);
+        for index in 0u64..1024 {
+            let rel_x = (index % 32) as i32;
+            let rel_z = (index / 32) as i32;
+            coords.push((region_x * 32 + rel_x, region_z * 32 + rel_z));
+        }
+        coords
+    }
+
+    /// Reads and classifies the chunk at `(chunk_x, chunk_z)` without
+    /// trusting it: checks the declared frame length against the sectors
+    /// the location table allocated, the compression id, whether the
+    /// payload actually decompresses, whether the result deserializes
+    /// into [`ChunkData`], and whether the stored coordinates match the
+    /// slot. Returns `Ok(None)` for an empty (never-written) slot.
+    pub fn diagnose_chunk(&self, chunk_x: i32, chunk_z: i32) -> Result<Option<ChunkIssue>> {
+        let _guard = self.lock.read().unwrap();
+
+        let path = self.region_path(chunk_x, chunk_z);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let mut file = File::open(&path)?;
+        let index = Self::chunk_index(chunk_x, chunk_z);
+        let (sector_offset, sector_count) = Self::read_location(&mut file, index)?;
+        if sector_offset == 0 || sector_count == 0 {
+            return Ok(None);
+        }
+
+        file.seek(SeekFrom::Start(sector_offset as u64 * SECTOR_SIZE))?;
+        let mut len_buf = [0u8; 4];
+        file.read_exact(&mut len_buf)?;
+        let len = u32::from_be_bytes(len_buf) as usize;
+        let allocated = sector_count as usize * SECTOR_SIZE as usize;
+        if len == 0 || 4 + len > allocated {
+            return Ok(Some(ChunkIssue::LengthOverflow));
+        }
+
+        let mut frame = vec![0u8; len];
+        file.read_exact(&mut frame)?;
+        let compression_byte = frame[0];
+        let payload = &frame[1..];
+
+        let compression_id = compression_byte & !EXTERNAL_FLAG;
+        if !matches!(compression_id, 1 | 2 | 3) {
+            return Ok(Some(ChunkIssue::UnknownCompression));
+        }
+
+        let compressed: Vec<u8> = if compression_byte & EXTERNAL_FLAG != 0 {
+            match std::fs::read(self.mcc_path(chunk_x, chunk_z)) {
+                Ok(bytes) => bytes,
+                Err(_) => return Ok(Some(ChunkIssue::DecompressFailed)),
+            }
+        } else {
+            payload.to_vec()
+        };
+
+        let nbt = match decompress(compression_id, &compressed) {
+            Ok(nbt) => nbt,
+            Err(_) => return Ok(Some(ChunkIssue::DecompressFailed)),
+        };
+
+        let chunk: ChunkData = match fastnbt::from_bytes(&nbt) {
+            Ok(chunk) => chunk,
+            Err(_) => return Ok(Some(ChunkIssue::DeserializeFailed)),
+        };
+
+        if chunk.x_pos != chunk_x || chunk.z_pos != chunk_z {
+            return Ok(Some(ChunkIssue::CoordinateMismatch));
+        }
+
+        Ok(None)
+    }
+
+    /// Zeroes out the location-table entry for `(chunk_x, chunk_z)` — a
+    /// logical delete. Its sectors become free for reuse by a later
+    /// `write_chunk`, but aren't reclaimed immediately.
+    pub fn delete_chunk(&self, chunk_x: i32, chunk_z: i32) -> Result<()> {
+        let _guard = self.lock.write().unwrap();
+
+        let path = self.region_path(chunk_x, chunk_z);
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let mut file = OpenOptions::new().read(true).write(true).open(&path)?;
+        let index = Self::chunk_index(chunk_x, chunk_z);
+        Self::write_location(&mut file, index, 0, 0)?;
+        Self::write_timestamp(&mut file, index, 0)?;
+        Ok(())
+    }
+}
+
+impl ChunkStorage for RegionFileStorage {
+    fn read_chunk(&self, chunk_x: i32, chunk_z: i32) -> Result<Option<Vec<u8>>> {
+        let _guard = self.lock.read().unwrap();
+
+        let path = self.region_path(chunk_x, chunk_z);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let mut file = File::open(&path)?;
+        let index = Self::chunk_index(chunk_x, chunk_z);
+        let (sector_offset, sector_count) = Self::read_location(&mut file, index)?;
+        if sector_offset == 0 || sector_count == 0 {
+            return Ok(None);
+        }
+
+        file.seek(SeekFrom::Start(sector_offset as u64 * SECTOR_SIZE))?;
+        let mut len_buf = [0u8; 4];
+        file.read_exact(&mut len_buf)?;
+        let len = u32::from_be_bytes(len_buf) as usize;
+        if len == 0 {
+            return Ok(None);
+        }
+
+        let mut frame = vec![0u8; len];
+        file.read_exact(&mut frame)?;
+        let compression_byte = frame[0];
+        let payload = &frame[1..];
+
+        let compressed: Vec<u8> = if compression_byte & EXTERNAL_FLAG != 0 {
+            std::fs::read(self.mcc_path(chunk_x, chunk_z))?
+        } else {
+            payload.to_vec()
+        };
+
+        let nbt = decompress(compression_byte & !EXTERNAL_FLAG, &compressed)?;
+        Ok(Some(nbt))
+    }
+
+    fn write_chunk(&self, chunk_x: i32, chunk_z: i32, data: &[u8]) -> Result<()> {
+        let _guard = self.lock.write().unwrap();
+
+        let path = self.region_path(chunk_x, chunk_z);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).read(true).write(true).open(&path)?;
+        if file.metadata()?.len() < HEADER_SECTORS * SECTOR_SIZE {
+            file.set_len(HEADER_SECTORS * SECTOR_SIZE)?;
+        }
+
+        let mut compressed = Vec::new();
+        let mut encoder = ZlibEncoder::new(&mut compressed, Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()?;
+
+        // In-region frame body: either the compression byte plus the
+        // payload itself, or (if it would overflow 255 sectors) just the
+        // compression byte with the external flag set, payload in `.mcc`.
+        let inline_sectors = (1 + compressed.len() + 4 + SECTOR_SIZE as usize - 1) / SECTOR_SIZE as usize;
+        let frame = if inline_sectors <= MAX_INLINE_SECTORS {
+            let mut frame = Vec::with_capacity(1 + compressed.len());
+            frame.push(2u8); // zlib
+            frame.extend_from_slice(&compressed);
+            frame
+        } else {
+            std::fs::write(self.mcc_path(chunk_x, chunk_z), &compressed)?;
+            vec![EXTERNAL_FLAG | 2u8]
+        };
+
+        let needed_sectors = ((4 + frame.len() as u64 + SECTOR_SIZE - 1) / SECTOR_SIZE) as usize;
+        if needed_sectors > MAX_INLINE_SECTORS {
+            return Err(anyhow!(
+                "chunk ({}, {}) stub frame unexpectedly exceeds {} sectors",
+                chunk_x,
+                chunk_z,
+                MAX_INLINE_SECTORS
+            ));
+        }
+
+        let index = Self::chunk_index(chunk_x, chunk_z);
+        let (own_offset, own_count) = Self::read_location(&mut file, index)?;
+
+        let mut occupied = Self::occupied_sectors(&mut file)?;
+        let mut sector_start = Self::find_free_run(&mut occupied, own_offset, own_count, needed_sectors);
+        if sector_start + needed_sectors > occupied.len() {
+            // No existing gap fits: grow the file at the end.
+            sector_start = occupied.len();
+        }
+
+        let byte_offset = sector_start as u64 * SECTOR_SIZE;
+        let padded_len = needed_sectors as u64 * SECTOR_SIZE;
+
+        file.seek(SeekFrom::Start(byte_offset))?;
+        let total_len = frame.len() as u32;
+        file.write_all(&total_len.to_be_bytes())?;
+        file.write_all(&frame)?;
+
+        let written = 4 + frame.len() as u64;
+        if written < padded_len {
+            file.write_all(&vec![0u8; (padded_len - written) as usize])?;
+        }
+
+        Self::write_location(&mut file, index, sector_start as u32, needed_sectors as u8)?;
+        Self::write_timestamp(&mut file, index, 0)?;
+
+        Ok(())
+    }
+}
+
+fn decompress(compression_id: u8, data: &[u8]) -> Result<Vec<u8>> {
+    match compression_id {
+        1 => {
+            let mut out = Vec::new();
+            GzDecoder::new(data).read_to_end(&mut out)?;
+            Ok(out)
+        }
+        2 => {
+            let mut out = Vec::new();
+            ZlibDecoder::new(data).read_to_end(&mut out)?;
+            Ok(out)
+        }
+        3 => Ok(data.to_vec()),
+        other => Err(anyhow!("unknown compression id {}", other)),
+    }
+}
@@ -0,0 +1,171 @@
+//! Region integrity scanning, with a configurable repair-or-regenerate
+//! policy per [`ChunkIssue`].
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use super::region_file::{ChunkIssue, RegionFileStorage};
+use super::ChunkStorage;
+
+/// What to do with a chunk once it's been classified with a given issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairAction {
+    /// Leave the chunk as-is; just report it.
+    Leave,
+    /// Zero out the chunk's location-table entry (logical delete).
+    Delete,
+    /// Hand the coordinate to the caller's regenerator and overwrite.
+    Regenerate,
+}
+
+/// Which [`RepairAction`] to apply for each [`ChunkIssue`] category.
+/// Categories absent from the map default to [`RepairAction::Leave`].
+pub type RepairPolicy = HashMap<ChunkIssue, RepairAction>;
+
+/// One chunk's outcome from a [`scan_region`] pass.
+#[derive(Debug, Clone)]
+pub struct ChunkReport {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub issue: ChunkIssue,
+    pub action_taken: RepairAction,
+}
+
+/// Walks every slot in region `(region_x, region_z)`, classifies each
+/// occupied chunk via [`RegionFileStorage::diagnose_chunk`], and applies
+/// `policy`'s action for whatever issue is found. `regenerate` produces
+/// fresh raw NBT bytes for a coordinate that needs regenerating (e.g. a
+/// `ChunkProvider`'s procedural generator). Returns a report covering
+/// only the chunks that had an issue; clean chunks are omitted.
+pub fn scan_region(
+    storage: &RegionFileStorage,
+    region_x: i32,
+    region_z: i32,
+    policy: &RepairPolicy,
+    mut regenerate: impl FnMut(i32, i32) -> Vec<u8>,
+) -> Result<Vec<ChunkReport>> {
+    let mut reports = Vec::new();
+
+    for (chunk_x, chunk_z) in RegionFileStorage::region_chunk_coords(region_x, region_z) {
+        let Some(issue) = storage.diagnose_chunk(chunk_x, chunk_z)? else {
+            continue;
+        };
+
+        let action = policy.get(&issue).copied().unwrap_or(RepairAction::Leave);
+        match action {
+            RepairAction::Leave => {}
+            RepairAction::Delete => storage.delete_chunk(chunk_x, chunk_z)?,
+            RepairAction::Regenerate => {
+                let fresh = regenerate(chunk_x, chunk_z);
+                storage.write_chunk(chunk_x, chunk_z, &fresh)?;
+            }
+        }
+
+        reports.push(ChunkReport {
+            chunk_x,
+            chunk_z,
+            issue,
+            action_taken: action,
+        });
+    }
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Each test gets its own directory under the system temp dir, named
+    // after the test so parallel `cargo test` runs don't collide.
+    fn fresh_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hoppermc-scan-test-{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // Corrupts chunk (0, 0)'s compression byte in-place so the next
+    // `diagnose_chunk` reports `ChunkIssue::UnknownCompression`, without
+    // touching the location table (the chunk still looks "occupied").
+    fn corrupt_compression_byte(dir: &std::path::Path) {
+        use std::io::{Seek, SeekFrom, Write};
+        let path = dir.join("r.0.0.mca");
+        let mut file = std::fs::OpenOptions::new().write(true).open(path).unwrap();
+        // Sector 2 (right after the 2-sector header) is where chunk (0, 0)'s
+        // single-sector write lands; byte 4 is the compression byte, right
+        // after the 4-byte frame length.
+        file.seek(SeekFrom::Start(2 * 4096 + 4)).unwrap();
+        file.write_all(&[0xFFu8]).unwrap();
+    }
+
+    #[test]
+    fn test_leave_reports_without_touching_the_chunk() {
+        let dir = fresh_dir("leave");
+        let storage = RegionFileStorage::new(&dir);
+        storage.write_chunk(0, 0, b"hello").unwrap();
+        corrupt_compression_byte(&dir);
+
+        let policy = RepairPolicy::new();
+        let reports = scan_region(&storage, 0, 0, &policy, |_, _| unreachable!()).unwrap();
+
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].issue, ChunkIssue::UnknownCompression);
+        assert_eq!(reports[0].action_taken, RepairAction::Leave);
+        // Still there — `Leave` must not have deleted or regenerated it.
+        assert!(storage.diagnose_chunk(0, 0).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_delete_action_clears_the_chunk() {
+        let dir = fresh_dir("delete");
+        let storage = RegionFileStorage::new(&dir);
+        storage.write_chunk(0, 0, b"hello").unwrap();
+        corrupt_compression_byte(&dir);
+
+        let mut policy = RepairPolicy::new();
+        policy.insert(ChunkIssue::UnknownCompression, RepairAction::Delete);
+        let reports = scan_region(&storage, 0, 0, &policy, |_, _| unreachable!()).unwrap();
+
+        assert_eq!(reports[0].action_taken, RepairAction::Delete);
+        assert_eq!(storage.diagnose_chunk(0, 0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_regenerate_action_overwrites_with_fresh_data() {
+        let dir = fresh_dir("regenerate");
+        let storage = RegionFileStorage::new(&dir);
+        storage.write_chunk(0, 0, b"hello").unwrap();
+        corrupt_compression_byte(&dir);
+
+        let mut policy = RepairPolicy::new();
+        policy.insert(ChunkIssue::UnknownCompression, RepairAction::Regenerate);
+        let reports = scan_region(&storage, 0, 0, &policy, |_, _| b"fresh chunk data".to_vec()).unwrap();
+
+        assert_eq!(reports[0].action_taken, RepairAction::Regenerate);
+        assert_eq!(storage.read_chunk(0, 0).unwrap(), Some(b"fresh chunk data".to_vec()));
+    }
+
+    #[test]
+    fn test_clean_chunks_are_omitted_from_the_report() {
+        let dir = fresh_dir("clean");
+        let storage = RegionFileStorage::new(&dir);
+        let chunk = crate::nbt::ChunkData {
+            data_version: crate::nbt::get_data_version(),
+            x_pos: 1,
+            z_pos: 1,
+            y_pos: -4,
+            status: "minecraft:full".to_string(),
+            last_update: 0,
+            inhabited_time: 0,
+            is_light_on: None,
+            sections: Vec::new(),
+        };
+        storage.write_chunk(1, 1, &fastnbt::to_bytes(&chunk).unwrap()).unwrap();
+
+        let reports = scan_region(&storage, 0, 0, &RepairPolicy::new(), |_, _| unreachable!()).unwrap();
+        assert!(reports.is_empty());
+    }
+}